@@ -12,7 +12,8 @@ export type ServerConfig = {
     tunnel: {
         enabled: boolean,
         provider: "ngrok"
-    }
+    },
+    icon?: string | null, // data URL of server-icon.png, only sent by list_servers
 }
 
 export type ActiveServerInfo = {
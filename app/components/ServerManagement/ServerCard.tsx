@@ -13,9 +13,14 @@ import { notifyError } from "@/app/utils/alerts";
 import { resetLogs } from "@/app/utils/server/resetLogs";
 import { stopServer } from "@/app/utils/server/serverActions";
 import { useRouter } from "next/navigation";
+import { AlertModalRenderer } from "../misc/AlertModal";
 
 const MAX_BLOCKS = 16;
 
+// start_server fails with this prefix until the EULA is accepted for the server
+const EULA_NOT_ACCEPTED = "EULA_NOT_ACCEPTED";
+const EULA_URL = "https://aka.ms/MinecraftEULA";
+
 export const ServerCard = ({
     server
 }: {
@@ -29,10 +34,12 @@ export const ServerCard = ({
         ram_gb,
         path,
         created_at,
+        icon,
     } = server;
 
     const isMac = useAtomValue(isMacAtom);
     const [serverSettingsModalOpen, setServerSettingsModalOpen] = useState(false);
+    const [eulaModalOpen, setEulaModalOpen] = useState(false);
     const [activeServer, setActiveServer] = useAtom(activeServerAtom);
 
     const isActive = activeServer?.server_id === server.id;
@@ -53,6 +60,29 @@ export const ServerCard = ({
         forge: "/forge_mc.png",
     }[loader] ?? "/vanilla_mc.png";
 
+    const startServer = async () => {
+        setGlobalShowLoader("Starting server...");
+
+        resetLogs(); // Reset old logs
+
+        const startedServer = await invoke<ActiveServerInfo>("start_server", { server });
+        setActiveServer(startedServer);
+
+        rpcEnabled && await invoke("discord_set_server_running", { serverName: server.name });
+
+        router.replace('/terminal');
+    }
+
+    const handleError = (err: unknown) => {
+        if (String(err).startsWith(EULA_NOT_ACCEPTED)) {
+            setEulaModalOpen(true);
+            return;
+        }
+
+        notifyError(err?.toString() ?? "Failed to start server");
+        console.error(err);
+    }
+
     const handlePlayStop = async () => {
         try {
             if (isActive) {
@@ -60,21 +90,21 @@ export const ServerCard = ({
             } else if (isAnotherRunning) {
                 notifyError("Another server is already running.");
             } else {
-                setGlobalShowLoader("Starting server...");
-
-                resetLogs(); // Reset old logs
-
-                const startedServer = await invoke<ActiveServerInfo>("start_server", { server });
-                setActiveServer(startedServer);
-
-                rpcEnabled && await invoke("discord_set_server_running", { serverName: server.name });
-
-                router.replace('/terminal');
-
+                await startServer();
             }
         } catch (err) {
-            notifyError(err?.toString() ?? "Failed to start server");
-            console.error(err);
+            handleError(err);
+        } finally {
+            setHideGlobalLoader();
+        }
+    }
+
+    const handleAcceptEula = async () => {
+        try {
+            await invoke("accept_eula", { serverId: server.id });
+            await startServer();
+        } catch (err) {
+            handleError(err);
         } finally {
             setHideGlobalLoader();
         }
@@ -86,6 +116,16 @@ export const ServerCard = ({
                 <ServerSettingsModal setIsOpen={setServerSettingsModalOpen} server={server} />
             </ModalRenderer>
 
+            <AlertModalRenderer
+                isOpen={eulaModalOpen}
+                setIsOpen={setEulaModalOpen}
+                onConfirm={handleAcceptEula}
+                title="Minecraft EULA"
+                description={`To run '${server.name}' you need to agree to the Minecraft EULA (${EULA_URL}).`}
+                confirmText="Agree & Start"
+                confirmVariant="warning"
+            />
+
             <div className={`w-51 h-65 corner-squircle bg-neutral-800 flex flex-col overflow-hidden cursor-pointer relative shadow-md ${isMac ? 'rounded-[30px]' : 'rounded-[50px]'} ${isActive && 'border-2 border-green-500'} cyberpunk:bg-red-900/70 cyberpunk:rounded-none cyberpunk:rounded-tl-[40px] cyberpunk:corner-tl-bevel cyberpunk:rounded-br-[30px] cyberpunk:corner-br-bevel cyberpunk-border cyberpunk-glow`}>
                 <div className="absolute inset-0 flex flex-col pointer-events-none">
                     <button 
@@ -130,9 +170,12 @@ export const ServerCard = ({
                 </div>
 
                 <div className="h-full flex flex-col relative">
+                    {/* The server's own icon is 64x64, keep its pixels sharp */}
                     <div 
-                        className="flex-1 bg-[url('/minecraft.jpg')] bg-cover bg-center mask-[linear-gradient(to_bottom,black_30%,transparent)]
-                        [-webkit-mask-image:linear-gradient(to_bottom,black_30%,transparent)]"
+                        className={`flex-1 bg-cover bg-center mask-[linear-gradient(to_bottom,black_30%,transparent)]
+                        [-webkit-mask-image:linear-gradient(to_bottom,black_30%,transparent)]
+                        ${icon ? "[image-rendering:pixelated]" : "bg-[url('/minecraft.jpg')]"}`}
+                        style={icon ? { backgroundImage: `url("${icon}")` } : undefined}
                     />
 
                     <div className="flex-1 p-2 px-3 flex flex-col justify-center text-white cyberpunk:text-red-600 font-mono text-sm gap-1">
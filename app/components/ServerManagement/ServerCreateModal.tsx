@@ -12,6 +12,7 @@ import { notifyError, notifySuccess } from "@/app/utils/alerts";
 import { isValidInstanceName } from "@/app/utils/regexValidator";
 import { LoaderRenderer } from "../misc/Loader";
 import { refreshServers } from "@/app/utils/server/refreshServers";
+import { listen } from "@tauri-apps/api/event";
 
 export type LoaderType = "vanilla" | "fabric" | "forge";
 export type SupportedLoadersType = {
@@ -47,6 +48,7 @@ export const ServerCreateModal = ({
     const [loadingLoaders, setLoadingLoaders] = useState(false);
     const [ramGB, setRamGB] = useState<number>(2);
     const [loading, setLoading] = useState(false);
+    const [installerLine, setInstallerLine] = useState<string | null>(null);
 
     useEffect(() => {
         function convertVersionList() {
@@ -57,6 +59,22 @@ export const ServerCreateModal = ({
         convertVersionList();
     }, [versions]);
 
+    // Fabric and Forge installers report their progress while the server is created
+    useEffect(() => {
+        if (!loading) return;
+
+        let unlisten: any;
+
+        listen<string>("installer-log", (event) => {
+            setInstallerLine(event.payload);
+        }).then(fn => unlisten = fn);
+
+        return () => {
+            if (unlisten) unlisten();
+            setInstallerLine(null);
+        }
+    }, [loading]);
+
     useSupportedLoaders({
         instanceVersion,
         setSelectedLoader,
@@ -153,7 +171,7 @@ export const ServerCreateModal = ({
                 exit={{ y: -10 }}
                 transition={{ duration: 0.2 }}
             >
-                {(loadingLoaders || loading) && <LoaderRenderer text="Creating Server..." />}
+                {(loadingLoaders || loading) && <LoaderRenderer text={installerLine ?? "Creating Server..."} />}
 
                 <IoCloseCircle 
                     size={30} 
@@ -8,7 +8,7 @@ import { IoCloseCircle } from "react-icons/io5";
 import { Loader, LoaderRenderer } from "../misc/Loader";
 import { SwitchToggle } from "../misc/Switch";
 import { SelectMenu } from "../misc/SelectMenu";
-import { readServerConfig, readServerProperties, updateServerConfig, updateServerProperties } from "@/app/utils/server/serverProperties";
+import { propertyIssues, readServerConfig, readServerProperties, updateServerConfig, updateServerProperties } from "@/app/utils/server/serverProperties";
 import { refreshServers } from "@/app/utils/server/refreshServers";
 import DiscreteSlider from "../misc/Slider";
 import { ramMarks } from "./ServerCreateModal";
@@ -225,7 +225,13 @@ export const ServerSettingsModal = ({
                 hideProgressBar: false
             });
         } catch (err) {
-            notifyError("Something went wrong, please reopen settings and try again!");
+            const issues = propertyIssues(err);
+
+            if (issues) {
+                issues.forEach(issue => notifyError(issue.message));
+            } else {
+                notifyError("Something went wrong, please reopen settings and try again!");
+            }
             console.error("Edit server properties failed:", err);
         } finally {
             setLoading(false);
@@ -14,6 +14,27 @@ export async function readServerProperties(serverPath: string) {
     return props;
 }
 
+export type PropertyIssue = {
+    key: string;
+    kind: "unknown" | "deprecated" | "invalid";
+    message: string;
+};
+
+const INVALID_PROPERTIES = "INVALID_PROPERTIES:";
+
+// Issues of rejected values, keyed by property, or null for any other error
+export function propertyIssues(err: unknown): PropertyIssue[] | null {
+    if (typeof err !== "string" || !err.startsWith(INVALID_PROPERTIES)) {
+        return null;
+    }
+
+    try {
+        return JSON.parse(err.slice(INVALID_PROPERTIES.length));
+    } catch {
+        return null;
+    }
+}
+
 export async function updateServerProperties(serverPath: string, form: ServerProperties) {
     if (!form) {
         throw new Error("An error has occured!");
@@ -0,0 +1,724 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zip::ZipArchive;
+
+use crate::commands::remote_backups::upload_to_enabled_destinations;
+use crate::commands::server_creation::CreateServerResult;
+use crate::commands::server_management::{
+    find_server, is_server_running, level_name, stop_active_server, ServerConfig,
+};
+use crate::state::app_state::AppState;
+use crate::utils::archive::{extract_zip_filtered, zip_dir};
+use crate::utils::crypto::{decrypt_file, encrypt_file, is_encrypted_file, EncryptionInfo};
+use crate::utils::path::{
+    backups_dir, cleanup_empty_parent_dir, cleanup_server_dir, servers_dir, validate_dir_name,
+    validate_id,
+};
+
+/// CREATING AND LISTING BACKUPS
+
+// A backup is a zip of the whole server directory (minus logs) stored in
+// `backups_dir(server_id)/<id>.zip`, with a `<id>.json` sidecar describing it.
+
+// Top level folders that are never worth backing up
+const SKIPPED_DIRS: [&str; 2] = ["logs", "crash-reports"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupInfo {
+    pub id: String,
+    pub server_id: String,
+    pub server_name: String,
+    pub version: String,
+    pub level_name: String,
+    pub created_at: i64,
+    pub size_bytes: u64,
+
+    // Set when the archive was encrypted with a passphrase
+    #[serde(default)]
+    pub encryption: Option<EncryptionInfo>,
+}
+
+// Both ids come from the frontend (or a sidecar), so they are checked before being joined
+pub fn backup_archive_path(server_id: &str, backup_id: &str) -> Result<PathBuf, String> {
+    validate_id(server_id)?;
+    validate_id(backup_id)?;
+    Ok(backups_dir(server_id).join(format!("{}.zip", backup_id)))
+}
+
+pub fn backup_info_path(server_id: &str, backup_id: &str) -> Result<PathBuf, String> {
+    validate_id(server_id)?;
+    validate_id(backup_id)?;
+    Ok(backups_dir(server_id).join(format!("{}.json", backup_id)))
+}
+
+pub fn read_backup_info(server_id: &str, backup_id: &str) -> Result<BackupInfo, String> {
+    let raw = fs::read_to_string(backup_info_path(server_id, backup_id)?)
+        .map_err(|_| format!("Backup {} not found", backup_id))?;
+
+    serde_json::from_str(&raw).map_err(|e| e.to_string())
+}
+
+/// A backup archive ready to be read. Encrypted backups are decrypted (and thereby
+/// fully verified) into a temporary file, which is removed again on drop.
+pub struct OpenedBackup {
+    pub path: PathBuf,
+    temporary: bool,
+}
+
+impl OpenedBackup {
+    pub fn open(&self) -> Result<fs::File, String> {
+        fs::File::open(&self.path).map_err(|e| e.to_string())
+    }
+}
+
+impl Drop for OpenedBackup {
+    fn drop(&mut self) {
+        if self.temporary {
+            fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+pub fn open_backup(info: &BackupInfo, passphrase: Option<&str>) -> Result<OpenedBackup, String> {
+    let archive = backup_archive_path(&info.server_id, &info.id)?;
+
+    if !archive.exists() {
+        return Err(format!("Backup {} not found", info.id));
+    }
+
+    // The sidecar isn't authenticated, so the archive itself must agree with it. Otherwise
+    // clearing `encryption` would let a swapped plaintext archive restore unchecked
+    let encrypted = is_encrypted_file(&archive)?;
+
+    if encrypted != info.encryption.is_some() {
+        return Err(format!(
+            "Backup {} has been modified: its archive and description don't match",
+            info.id
+        ));
+    }
+
+    let Some(encryption) = &info.encryption else {
+        return Ok(OpenedBackup {
+            path: archive,
+            temporary: false,
+        });
+    };
+
+    let passphrase = passphrase
+        .filter(|p| !p.is_empty())
+        .ok_or("This backup is encrypted, enter its passphrase")?;
+
+    // A name of its own, another browse or restore may have the same backup open
+    let opened = OpenedBackup {
+        path: archive.with_extension(format!("zip.{}.decrypted", Uuid::new_v4())),
+        temporary: true,
+    };
+
+    decrypt_file(&archive, &opened.path, passphrase, encryption)?;
+
+    Ok(opened)
+}
+
+// Decrypting runs Argon2 and reads the whole archive, so it is kept off the async runtime
+pub async fn open_backup_blocking(
+    info: &BackupInfo,
+    passphrase: Option<String>,
+) -> Result<OpenedBackup, String> {
+    let info = info.clone();
+
+    tokio::task::spawn_blocking(move || open_backup(&info, passphrase.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Returns the world folders of a level that exist on disk: the overworld plus
+/// the Bukkit-style `_nether` / `_the_end` folders when the server split them out.
+pub fn world_dir_names(server_path: &Path, level: &str) -> Vec<String> {
+    [
+        level.to_string(),
+        format!("{}_nether", level),
+        format!("{}_the_end", level),
+    ]
+    .into_iter()
+    .filter(|name| server_path.join(name).is_dir())
+    .collect()
+}
+
+// Writes a console command to the running server if it is the one with this id
+pub fn send_if_running(state: &AppState, server_id: &str, command: &str) -> bool {
+    let mut active = state.active_server.lock().unwrap();
+
+    let Some(server) = active.as_mut().filter(|s| s.server_id == server_id) else {
+        return false;
+    };
+
+    let Some(stdin) = server.mc_child.stdin.as_mut() else {
+        return false;
+    };
+
+    stdin.write_all(format!("{}\n", command).as_bytes()).is_ok()
+}
+
+/// Runs `work` on a blocking thread. If the server is running, autosave is paused and the
+/// world flushed to disk first, then turned back on once `work` is done.
+pub async fn with_world_flushed<T, F>(
+    state: &AppState,
+    server_id: &str,
+    work: F,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let running = send_if_running(state, server_id, "save-off");
+
+    if running {
+        send_if_running(state, server_id, "save-all flush");
+        // Give the server time to finish writing chunks
+        tokio::time::sleep(Duration::from_secs(3)).await;
+    }
+
+    let result = tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+
+    if running {
+        send_if_running(state, server_id, "save-on");
+    }
+
+    result
+}
+
+/// Creates a backup of a server. If the server is running, autosave is paused
+/// and the world flushed to disk while the archive is written.
+///
+/// With a passphrase the archive is encrypted before anything else (uploads included) sees it.
+pub async fn backup_server(
+    server: &ServerConfig,
+    state: &AppState,
+    passphrase: Option<&str>,
+) -> Result<BackupInfo, String> {
+    let now = Utc::now();
+    let id = now.format("%Y-%m-%d_%H-%M-%S").to_string();
+    let archive = backup_archive_path(&server.id, &id)?;
+
+    if archive.exists() {
+        return Err("A backup was already created this second".into());
+    }
+
+    let server_path = PathBuf::from(&server.path);
+    let zipped = archive.clone();
+
+    let result = with_world_flushed(state, &server.id, move || {
+        zip_dir(&server_path, &zipped, &|rel| SKIPPED_DIRS.contains(&rel))
+    })
+    .await;
+
+    if let Err(e) = result {
+        fs::remove_file(&archive).ok();
+        return Err(format!("Failed to create backup: {}", e));
+    }
+
+    let mut encryption = None;
+
+    if let Some(passphrase) = passphrase.filter(|p| !p.is_empty()) {
+        let sealed = archive.with_extension("zip.enc");
+        let (plain, passphrase) = (archive.clone(), passphrase.to_string());
+        let sealed_path = sealed.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            encrypt_file(&plain, &sealed_path, &passphrase).and_then(|info| {
+                fs::rename(&sealed_path, &plain)
+                    .map(|_| info)
+                    .map_err(|e| e.to_string())
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+
+        match result {
+            Ok(info) => encryption = Some(info),
+            Err(e) => {
+                fs::remove_file(&sealed).ok();
+                fs::remove_file(&archive).ok();
+                return Err(format!("Failed to encrypt backup: {}", e));
+            }
+        }
+    }
+
+    let info = BackupInfo {
+        id: id.clone(),
+        server_id: server.id.clone(),
+        server_name: server.name.clone(),
+        version: server.version.clone(),
+        level_name: level_name(&server.path),
+        created_at: now.timestamp(),
+        size_bytes: fs::metadata(&archive).map(|m| m.len()).unwrap_or(0),
+        encryption,
+    };
+
+    fs::write(
+        backup_info_path(&server.id, &id)?,
+        serde_json::to_string_pretty(&info).unwrap(),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn create_backup(
+    server_id: String,
+    passphrase: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<BackupInfo, String> {
+    let server = find_server(&server_id)?;
+    let info = backup_server(&server, &state, passphrase.as_deref()).await?;
+
+    // Off-site copies report through `backup-upload` events and never fail the local backup
+    let app = state.app_handle.lock().unwrap().clone();
+    upload_to_enabled_destinations(&info, app).await;
+
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn list_backups(server_id: String) -> Result<Vec<BackupInfo>, String> {
+    validate_id(&server_id)?;
+
+    let dir = backups_dir(&server_id);
+    let mut backups = Vec::new();
+
+    // No backups taken yet
+    if !dir.exists() {
+        return Ok(backups);
+    }
+
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+
+        // Skip sidecars whose archive went missing (or whose name isn't a backup id)
+        if !backup_archive_path(&server_id, id).is_ok_and(|a| a.exists()) {
+            continue;
+        }
+
+        if let Ok(info) = read_backup_info(&server_id, id) {
+            backups.push(info);
+        }
+    }
+
+    // Newest first
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+
+    Ok(backups)
+}
+
+#[tauri::command]
+pub fn delete_backup(server_id: String, backup_id: String) -> Result<(), String> {
+    let archive = backup_archive_path(&server_id, &backup_id)?;
+
+    if !archive.exists() {
+        return Err(format!("Backup {} not found", backup_id));
+    }
+
+    fs::remove_file(archive).map_err(|e| e.to_string())?;
+    fs::remove_file(backup_info_path(&server_id, &backup_id)?).ok();
+
+    Ok(())
+}
+
+/// RESTORING
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreResult {
+    // Where the world that was replaced got moved to
+    pub safety_snapshot: Option<String>,
+}
+
+// Where live files replaced by a restore are moved to, one folder per restore
+fn safety_snapshot_dir(server_id: &str) -> PathBuf {
+    backups_dir(server_id)
+        .join("pre-restore")
+        .join(Utc::now().format("%Y-%m-%d_%H-%M-%S").to_string())
+}
+
+/// Maps an archived world entry (e.g. `old_world_nether/region/r.0.0.mca`) to the
+/// matching folder of the current level, or `None` for anything that isn't world data.
+///
+/// Paths with `..`, a root or a drive are refused, they could point outside the world.
+fn map_world_entry(rel: &Path, backup_level: &str, level: &str) -> Option<PathBuf> {
+    if rel.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return None;
+    }
+
+    let mut components = rel.components();
+    let top = components.next()?.as_os_str().to_str()?;
+
+    let target = if top == backup_level {
+        level.to_string()
+    } else if top == format!("{}_nether", backup_level) {
+        format!("{}_nether", level)
+    } else if top == format!("{}_the_end", backup_level) {
+        format!("{}_the_end", level)
+    } else {
+        return None;
+    };
+
+    Some(PathBuf::from(target).join(components.as_path()))
+}
+
+// Swaps the live world for the one in the backup, putting the live one back on failure
+fn restore_world(
+    server_id: &str,
+    server_path: &Path,
+    level: &str,
+    backup: &BackupInfo,
+    archive: &OpenedBackup,
+) -> Result<RestoreResult, String> {
+    // Move the current world aside instead of deleting it
+    let current_worlds = world_dir_names(server_path, level);
+    let mut safety_snapshot = None;
+
+    if !current_worlds.is_empty() {
+        let snapshot = safety_snapshot_dir(server_id);
+
+        fs::create_dir_all(&snapshot).map_err(|e| e.to_string())?;
+
+        for (i, name) in current_worlds.iter().enumerate() {
+            if let Err(e) = fs::rename(server_path.join(name), snapshot.join(name)) {
+                // Don't leave the server without the folders already moved
+                for moved in &current_worlds[..i] {
+                    fs::rename(snapshot.join(moved), server_path.join(moved)).ok();
+                }
+                fs::remove_dir(&snapshot).ok();
+
+                return Err(format!("Failed to move {} aside: {}", name, e));
+            }
+        }
+
+        safety_snapshot = Some(snapshot);
+    }
+
+    let file = archive.open()?;
+    let result = extract_zip_filtered(file, server_path, &|rel| {
+        map_world_entry(rel, &backup.level_name, level)
+    });
+
+    let failure = match result {
+        Ok(0) => Some("Backup does not contain any world data".to_string()),
+        Ok(_) => None,
+        Err(e) => Some(format!("Failed to restore backup: {}", e)),
+    };
+
+    // Put the previous world back if the restore did not go through
+    if let Some(err) = failure {
+        for name in world_dir_names(server_path, level) {
+            fs::remove_dir_all(server_path.join(name)).ok();
+        }
+
+        if let Some(snapshot) = &safety_snapshot {
+            for name in &current_worlds {
+                fs::rename(snapshot.join(name), server_path.join(name)).ok();
+            }
+            fs::remove_dir(snapshot).ok();
+        }
+
+        return Err(err);
+    }
+
+    Ok(RestoreResult {
+        safety_snapshot: safety_snapshot.map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    server_id: String,
+    backup_id: String,
+    passphrase: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<RestoreResult, String> {
+    let server = find_server(&server_id)?;
+    let backup = read_backup_info(&server_id, &backup_id)?;
+
+    // Decrypt and verify before anything on disk is touched
+    let archive = open_backup_blocking(&backup, passphrase).await?;
+
+    if is_server_running(&state, &server_id) {
+        // Waits for the server process to exit
+        let state = state.inner().clone();
+        tokio::task::spawn_blocking(move || stop_active_server(&state))
+            .await
+            .map_err(|e| e.to_string())??;
+    }
+
+    let server_path = PathBuf::from(&server.path);
+    let level = level_name(&server.path);
+
+    tokio::task::spawn_blocking(move || {
+        restore_world(&server_id, &server_path, &level, &backup, &archive)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn restore_backup_as_new(
+    server_id: String,
+    backup_id: String,
+    name: String,
+    passphrase: Option<String>,
+) -> Result<CreateServerResult, String> {
+    let name = name.trim().to_string();
+    validate_dir_name(&name)?;
+
+    let backup = read_backup_info(&server_id, &backup_id)?;
+    // The version comes from the sidecar, which could have been edited
+    validate_dir_name(&backup.version)?;
+
+    let archive = open_backup_blocking(&backup, passphrase).await?;
+
+    let mut server_path = servers_dir();
+    server_path.push(&backup.version);
+    server_path.push(&name);
+
+    if server_path.exists() {
+        return Err("Server already exists".into());
+    }
+
+    fs::create_dir_all(&server_path).map_err(|e| e.to_string())?;
+
+    let target = server_path.clone();
+    let result = tokio::task::spawn_blocking(move || -> Result<ServerConfig, String> {
+        let file = archive.open()?;
+        extract_zip_filtered(file, &target, &|rel| Some(rel.to_path_buf()))?;
+
+        let raw = fs::read_to_string(target.join("cubely.json"))
+            .map_err(|_| "Backup does not contain cubely.json".to_string())?;
+        let mut config: ServerConfig = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+
+        // A brand new server that only shares the files of the backup
+        config.id = Uuid::new_v4().to_string();
+        config.name = name;
+        config.path = target.to_string_lossy().to_string();
+        config.created_at = Utc::now().timestamp();
+
+        fs::write(
+            target.join("cubely.json"),
+            serde_json::to_string_pretty(&config).unwrap(),
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(config)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+
+    // Rollback on failure
+    if let Err(err) = result {
+        cleanup_server_dir(&server_path);
+
+        if let Some(version_dir) = server_path.parent() {
+            cleanup_empty_parent_dir(&version_dir.to_path_buf());
+        }
+
+        return Err(err);
+    }
+
+    Ok(CreateServerResult {
+        success: true,
+        path: server_path.to_string_lossy().to_string(),
+    })
+}
+
+/// BROWSING AND PARTIAL RESTORE
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub path: String,
+    pub size_bytes: u64,
+    pub is_dir: bool,
+}
+
+#[tauri::command]
+pub async fn browse_backup(
+    server_id: String,
+    backup_id: String,
+    passphrase: Option<String>,
+) -> Result<Vec<BackupEntry>, String> {
+    let backup = read_backup_info(&server_id, &backup_id)?;
+    let opened = open_backup_blocking(&backup, passphrase).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut archive = ZipArchive::new(opened.open()?).map_err(|e| e.to_string())?;
+        let mut entries = Vec::new();
+
+        for i in 0..archive.len() {
+            let file = archive.by_index(i).map_err(|e| e.to_string())?;
+
+            entries.push(BackupEntry {
+                path: file.name().trim_end_matches('/').to_string(),
+                size_bytes: file.size(),
+                is_dir: file.is_dir(),
+            });
+        }
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(entries)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Swaps the selected (archived path, live path) pairs for their backed up versions
+fn restore_entries(
+    server_id: &str,
+    server_path: &Path,
+    level: &str,
+    backup: &BackupInfo,
+    archive: &OpenedBackup,
+    selected: &[(PathBuf, PathBuf)],
+) -> Result<RestoreResult, String> {
+    // Move the live versions aside
+    let snapshot = safety_snapshot_dir(server_id);
+    let mut moved: Vec<PathBuf> = Vec::new();
+
+    let put_back = |moved: &[PathBuf]| {
+        for target in moved {
+            fs::rename(snapshot.join(target), server_path.join(target)).ok();
+        }
+        fs::remove_dir_all(&snapshot).ok();
+    };
+
+    for (_, target) in selected {
+        let live = server_path.join(target);
+
+        if !live.exists() {
+            continue;
+        }
+
+        let aside = snapshot.join(target);
+        let result = match aside.parent() {
+            Some(p) => fs::create_dir_all(p),
+            None => Ok(()),
+        }
+        .and_then(|_| fs::rename(&live, &aside));
+
+        if let Err(e) = result {
+            put_back(&moved);
+            return Err(format!("Failed to move {} aside: {}", target.display(), e));
+        }
+
+        moved.push(target.clone());
+    }
+
+    let file = archive.open()?;
+    let result = extract_zip_filtered(file, server_path, &|rel| {
+        selected
+            .iter()
+            .find(|(wanted, _)| rel.starts_with(wanted))
+            .and_then(|_| map_world_entry(rel, &backup.level_name, level))
+    });
+
+    let failure = match result {
+        Ok(0) => Some("None of the selected paths exist in this backup".to_string()),
+        Ok(_) => None,
+        Err(e) => Some(format!("Failed to restore files: {}", e)),
+    };
+
+    // Put the live files back if the restore did not go through
+    if let Some(err) = failure {
+        for (_, target) in selected {
+            let live = server_path.join(target);
+            if live.is_dir() {
+                fs::remove_dir_all(&live).ok();
+            } else {
+                fs::remove_file(&live).ok();
+            }
+        }
+
+        put_back(&moved);
+
+        return Err(err);
+    }
+
+    Ok(RestoreResult {
+        safety_snapshot: (!moved.is_empty()).then(|| snapshot.to_string_lossy().to_string()),
+    })
+}
+
+/// Restores only the given paths of a backup (e.g. `world/playerdata/<uuid>.dat`,
+/// `world/region/r.0.-1.mca`, a whole `world/DIM-1` or `world_nether` folder) into the
+/// live world.
+///
+/// The live versions of those paths are moved into a safety snapshot first.
+/// The server must be stopped, since it would overwrite the files on its next save.
+#[tauri::command]
+pub async fn restore_backup_entries(
+    server_id: String,
+    backup_id: String,
+    paths: Vec<String>,
+    passphrase: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<RestoreResult, String> {
+    if is_server_running(&state, &server_id) {
+        return Err("Stop the server before restoring files from a backup".into());
+    }
+
+    if paths.is_empty() {
+        return Err("Nothing selected to restore".into());
+    }
+
+    let server = find_server(&server_id)?;
+    let backup = read_backup_info(&server_id, &backup_id)?;
+
+    let server_path = PathBuf::from(&server.path);
+    let level = level_name(&server.path);
+
+    // Every selected path must point inside the world (a whole dimension folder included),
+    // never at jars or configs
+    let mut selected = Vec::new();
+
+    for path in &paths {
+        let rel = PathBuf::from(path.trim_matches('/'));
+        let target = map_world_entry(&rel, &backup.level_name, &level)
+            .ok_or(format!("{} is not a file or folder inside the world", path))?;
+
+        selected.push((rel, target));
+    }
+
+    let archive = open_backup_blocking(&backup, passphrase).await?;
+
+    tokio::task::spawn_blocking(move || {
+        restore_entries(
+            &server_id,
+            &server_path,
+            &level,
+            &backup,
+            &archive,
+            &selected,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,390 @@
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::commands::backup_manager::send_if_running;
+use crate::commands::server_management::{find_server, level_name, server_jar_version_json};
+use crate::nbt::{self, Tag, TAG_STRING};
+use crate::state::app_state::AppState;
+use crate::utils::{
+    archive::{copy_dir, extract_zip_filtered},
+    pack_meta::{parse_pack_meta, PackMeta},
+    path::resolve_server_file,
+};
+
+/// MANAGING THE DATAPACKS OF A WORLD (<world>/datapacks)
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatapackInfo {
+    pub name: String, // file or folder name inside datapacks/
+    pub id: String,   // "file/<name>", what the datapack command and level.dat use
+    pub is_zip: bool,
+    pub meta: Option<PackMeta>,
+    // None when level.dat doesn't list it yet, the server enables new packs on start
+    pub enabled: Option<bool>,
+    pub compatible: Option<bool>,
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatapackList {
+    pub server_format: Option<i32>,
+    pub packs: Vec<DatapackInfo>,
+}
+
+/// The pack format the server's Minecraft expects, `kind` is "data" or "resource".
+pub fn server_pack_format(server_path: &String, kind: &str) -> Option<i32> {
+    let version = server_jar_version_json(server_path)?;
+
+    match &version["pack_version"] {
+        // 1.14 - 1.17 used one number for both
+        Value::Number(n) => n.as_i64(),
+        // 1.21.9+ split it into major and minor
+        pack => pack[kind]
+            .as_i64()
+            .or_else(|| pack[format!("{}_major", kind)].as_i64()),
+    }
+    .map(|v| v as i32)
+}
+
+fn datapacks_dir(server_path: &str, world: &str) -> Result<PathBuf, String> {
+    resolve_server_file(server_path, &format!("{}/datapacks", world))
+}
+
+fn pack_path(server_path: &str, world: &str, name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid datapack name {}", name));
+    }
+
+    let path = datapacks_dir(server_path, world)?.join(name);
+    if !path.exists() {
+        return Err(format!("Datapack {} not found", name));
+    }
+
+    Ok(path)
+}
+
+// Reads pack.mcmeta from a datapack folder or zip
+fn read_meta(path: &Path) -> Result<PackMeta, String> {
+    if path.is_dir() {
+        let content = fs::read_to_string(path.join("pack.mcmeta"))
+            .map_err(|_| "Missing pack.mcmeta".to_string())?;
+        return parse_pack_meta(&content);
+    }
+
+    let mut archive = ZipArchive::new(fs::File::open(path).map_err(|e| e.to_string())?)
+        .map_err(|_| "Not a valid zip file".to_string())?;
+
+    let mut content = String::new();
+    archive
+        .by_name("pack.mcmeta")
+        .map_err(|_| "Missing pack.mcmeta".to_string())?
+        .read_to_string(&mut content)
+        .map_err(|e| e.to_string())?;
+
+    parse_pack_meta(&content)
+}
+
+// "file/<name>" entries of level.dat's Data/DataPacks/Enabled or Disabled list
+fn pack_ids(level: &Tag, list: &str) -> Vec<String> {
+    level
+        .get_path(&format!("Data/DataPacks/{}", list))
+        .and_then(Tag::as_list)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|i| i.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn level_dat_path(server_path: &str, world: &str) -> Result<PathBuf, String> {
+    resolve_server_file(server_path, &format!("{}/level.dat", world))
+}
+
+#[tauri::command]
+pub fn list_datapacks(server_id: String, world: Option<String>) -> Result<DatapackList, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let server_format = server_pack_format(&server.path, "data");
+
+    let level = nbt::read_file(&level_dat_path(&server.path, &world)?)
+        .ok()
+        .map(|f| f.root);
+    let enabled = level
+        .as_ref()
+        .map(|l| pack_ids(l, "Enabled"))
+        .unwrap_or_default();
+    let disabled = level
+        .as_ref()
+        .map(|l| pack_ids(l, "Disabled"))
+        .unwrap_or_default();
+
+    let Ok(entries) = fs::read_dir(datapacks_dir(&server.path, &world)?) else {
+        return Ok(DatapackList {
+            server_format,
+            packs: Vec::new(),
+        });
+    };
+
+    let mut packs = Vec::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_zip = path.extension().and_then(|e| e.to_str()) == Some("zip");
+
+        if !path.is_dir() && !is_zip {
+            continue;
+        }
+
+        let id = format!("file/{}", name);
+        let (meta, mut warning) = match read_meta(&path) {
+            Ok(meta) => (Some(meta), None),
+            Err(e) => (None, Some(e)),
+        };
+
+        let compatible = meta
+            .as_ref()
+            .zip(server_format)
+            .map(|(meta, format)| meta.supports(format));
+
+        if compatible == Some(false) {
+            let declared = match (&meta, meta.as_ref().and_then(|m| m.min_format)) {
+                (Some(m), Some(min)) => format!("{}-{}", min, m.max_format.unwrap_or(min)),
+                (Some(m), None) => m.pack_format.unwrap_or_default().to_string(),
+                _ => String::new(),
+            };
+            warning = Some(format!(
+                "Made for pack format {}, the server uses {}. It may not work.",
+                declared,
+                server_format.unwrap_or_default()
+            ));
+        }
+
+        packs.push(DatapackInfo {
+            enabled: if enabled.contains(&id) {
+                Some(true)
+            } else if disabled.contains(&id) {
+                Some(false)
+            } else {
+                None
+            },
+            name,
+            id,
+            is_zip,
+            meta,
+            compatible,
+            warning,
+        });
+    }
+
+    packs.sort_by_key(|p| p.name.to_lowercase());
+
+    Ok(DatapackList {
+        server_format,
+        packs,
+    })
+}
+
+/// Copies a datapack folder or zip into the world. Zips holding the pack inside a
+/// single top folder (like GitHub downloads) are unpacked into that folder.
+#[tauri::command]
+pub fn install_datapack(
+    server_id: String,
+    world: Option<String>,
+    source: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<DatapackInfo, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let source = PathBuf::from(source);
+    let dir = datapacks_dir(&server.path, &world)?;
+
+    let file_name = source
+        .file_name()
+        .ok_or("Invalid datapack path")?
+        .to_string_lossy()
+        .to_string();
+
+    let name = if source.is_dir() {
+        read_meta(&source)?;
+        let target = dir.join(&file_name);
+        if target.exists() {
+            return Err(format!(
+                "A datapack named {} is already installed",
+                file_name
+            ));
+        }
+        copy_dir(&source, &target, &|_| false)?;
+        file_name
+    } else {
+        let file = fs::File::open(&source).map_err(|e| e.to_string())?;
+        let archive = ZipArchive::new(file).map_err(|_| "Not a folder or zip file".to_string())?;
+
+        if archive.file_names().any(|n| n == "pack.mcmeta") {
+            read_meta(&source)?;
+            let target = dir.join(&file_name);
+            if target.exists() {
+                return Err(format!(
+                    "A datapack named {} is already installed",
+                    file_name
+                ));
+            }
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            fs::copy(&source, &target).map_err(|e| e.to_string())?;
+            file_name
+        } else {
+            // Look for <folder>/pack.mcmeta one level down
+            let inner = archive
+                .file_names()
+                .find(|n| n.matches('/').count() == 1 && n.ends_with("/pack.mcmeta"))
+                .map(|n| n.trim_end_matches("/pack.mcmeta").to_string())
+                .ok_or("The zip doesn't contain a datapack (no pack.mcmeta)")?;
+
+            let target = dir.join(&inner);
+            if target.exists() {
+                return Err(format!("A datapack named {} is already installed", inner));
+            }
+
+            let file = fs::File::open(&source).map_err(|e| e.to_string())?;
+            extract_zip_filtered(file, &dir, &|rel| {
+                rel.starts_with(&inner).then(|| rel.to_path_buf())
+            })?;
+
+            if let Err(e) = read_meta(&target) {
+                fs::remove_dir_all(&target).ok();
+                return Err(e);
+            }
+
+            inner
+        }
+    };
+
+    // A running server only discovers new packs on reload
+    send_if_running(&state, &server.id, "reload");
+
+    list_datapacks(server_id, Some(world))?
+        .packs
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or("Datapack was installed but couldn't be read".into())
+}
+
+// Moves an id between level.dat's Enabled and Disabled lists (both when `enabled` is None)
+fn set_level_dat_state(level_dat: &Path, id: &str, enabled: Option<bool>) -> Result<(), String> {
+    let mut file = nbt::read_file(level_dat)?;
+    let data = file
+        .root
+        .get_mut("Data")
+        .ok_or("level.dat has no Data compound")?;
+
+    if data.get("DataPacks").is_none() {
+        data.insert("DataPacks", Tag::Compound(Vec::new()));
+    }
+    let packs = data.get_mut("DataPacks").ok_or("Invalid level.dat")?;
+
+    for (list, keep) in [("Enabled", Some(true)), ("Disabled", Some(false))] {
+        let mut ids: Vec<Tag> = packs
+            .get(list)
+            .and_then(Tag::as_list)
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|t| t.as_str() != Some(id))
+            .collect();
+
+        if enabled == keep {
+            ids.push(Tag::String(id.to_string()));
+        }
+
+        packs.insert(
+            list,
+            Tag::List {
+                element: TAG_STRING,
+                items: ids,
+            },
+        );
+    }
+
+    fs::copy(level_dat, level_dat.with_file_name("level.dat_old"))
+        .map_err(|e| format!("Failed to back up level.dat: {}", e))?;
+
+    nbt::write_file(level_dat, &file)
+}
+
+// Pack names can hold spaces and quotes, the id is passed as a quoted string
+fn datapack_command(action: &str, id: &str) -> String {
+    format!(
+        "datapack {} \"{}\"",
+        action,
+        id.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// Uses `datapack enable/disable` while the server runs, edits level.dat otherwise.
+#[tauri::command]
+pub fn set_datapack_enabled(
+    server_id: String,
+    world: Option<String>,
+    name: String,
+    enabled: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    pack_path(&server.path, &world, &name)?;
+
+    let id = format!("file/{}", name);
+    let command = datapack_command(if enabled { "enable" } else { "disable" }, &id);
+
+    // Live changes only apply to the world the server has loaded
+    if world == level_name(&server.path) && send_if_running(&state, &server.id, &command) {
+        return Ok(());
+    }
+
+    set_level_dat_state(&level_dat_path(&server.path, &world)?, &id, Some(enabled))
+}
+
+/// Returns a warning when the server had the pack loaded, it only forgets it on `/reload`
+/// or a restart.
+#[tauri::command]
+pub fn remove_datapack(
+    server_id: String,
+    world: Option<String>,
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let path = pack_path(&server.path, &world, &name)?;
+    let id = format!("file/{}", name);
+
+    let live = world == level_name(&server.path)
+        && send_if_running(&state, &server.id, &datapack_command("disable", &id));
+
+    if path.is_dir() {
+        fs::remove_dir_all(&path)
+    } else {
+        fs::remove_file(&path)
+    }
+    .map_err(|e| format!("Failed to remove {}: {}", name, e))?;
+
+    // The server forgets missing packs on its own, a stopped one still lists it in level.dat
+    if !live {
+        set_level_dat_state(&level_dat_path(&server.path, &world)?, &id, None).ok();
+        return Ok(None);
+    }
+
+    Ok(Some(format!(
+        "{} was disabled and deleted, the server only forgets it after /reload or a restart",
+        name
+    )))
+}
@@ -0,0 +1,102 @@
+use std::{fs, path::PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::server_management::{
+    find_server, load_server_config, save_server_config, ServerConfig,
+};
+
+/// ACCEPTING THE MINECRAFT EULA
+
+pub const EULA_URL: &str = "https://aka.ms/MinecraftEULA";
+
+// Prefix of the error start_server returns, so the frontend can show the acceptance step
+pub const EULA_NOT_ACCEPTED: &str = "EULA_NOT_ACCEPTED";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EulaAcceptance {
+    pub accepted_by: String,
+    pub accepted_at: i64, // unix seconds
+}
+
+// Account name of whoever runs Cubely, when the frontend doesn't say who accepted
+fn system_user() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or("unknown".into())
+}
+
+// Same layout the server writes. The date is Java's Date.toString() format in UTC,
+// chrono has no zone abbreviations for local time (%Z would print an offset)
+fn write_eula_txt(server_path: &str) -> Result<(), String> {
+    let content = format!(
+        "#By changing the setting below to TRUE you are indicating your agreement to our EULA ({}).\n#{}\neula=true\n",
+        EULA_URL,
+        Utc::now().format("%a %b %d %H:%M:%S UTC %Y")
+    );
+
+    fs::write(PathBuf::from(server_path).join("eula.txt"), content).map_err(|e| e.to_string())
+}
+
+fn eula_txt_accepted(server_path: &str) -> bool {
+    fs::read_to_string(PathBuf::from(server_path).join("eula.txt"))
+        .map(|content| {
+            content
+                .lines()
+                .any(|l| l.trim().eq_ignore_ascii_case("eula=true"))
+        })
+        .unwrap_or(false)
+}
+
+/// Fails with EULA_NOT_ACCEPTED unless the acceptance is recorded in cubely.json,
+/// and makes sure eula.txt agrees with it.
+pub fn require_eula(server: &ServerConfig) -> Result<(), String> {
+    // The copy the frontend passed may predate the acceptance
+    let recorded = load_server_config(&server.path)
+        .ok()
+        .and_then(|c| c.eula)
+        .or(server.eula.clone());
+
+    if recorded.is_none() {
+        return Err(format!(
+            "{}: Accept the Minecraft EULA ({}) before starting this server",
+            EULA_NOT_ACCEPTED, EULA_URL
+        ));
+    }
+
+    if !eula_txt_accepted(&server.path) {
+        write_eula_txt(&server.path)?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_eula_acceptance(server_id: String) -> Result<Option<EulaAcceptance>, String> {
+    Ok(find_server(&server_id)?.eula)
+}
+
+/// Records who accepted the EULA and when, then writes eula.txt.
+#[tauri::command]
+pub fn accept_eula(
+    server_id: String,
+    accepted_by: Option<String>,
+) -> Result<EulaAcceptance, String> {
+    let mut server = find_server(&server_id)?;
+
+    let acceptance = EulaAcceptance {
+        accepted_by: accepted_by
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(system_user),
+        accepted_at: Utc::now().timestamp(),
+    };
+
+    write_eula_txt(&server.path)?;
+
+    server.eula = Some(acceptance.clone());
+    save_server_config(&server)?;
+
+    Ok(acceptance)
+}
@@ -44,6 +44,25 @@ impl JavaVersion {
         }
     }
 
+    // The oldest managed runtime that can run a version needing this Java (Mojang's javaVersion)
+    pub fn from_major(major: u32) -> Self {
+        match major {
+            0..=8 => JavaVersion::Java8,
+            9..=17 => JavaVersion::Java17,
+            18..=21 => JavaVersion::Java21,
+            _ => JavaVersion::Java25,
+        }
+    }
+
+    pub fn major(&self) -> u32 {
+        match self {
+            JavaVersion::Java8 => 8,
+            JavaVersion::Java17 => 17,
+            JavaVersion::Java21 => 21,
+            JavaVersion::Java25 => 25,
+        }
+    }
+
     pub fn java_binary(&self, base: &PathBuf) -> PathBuf {
         #[cfg(target_os = "windows")]
         let bin = "bin/java.exe";
@@ -0,0 +1,417 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::server_management::{find_server, is_server_running, level_name};
+use crate::nbt::{self, Tag};
+use crate::state::app_state::AppState;
+use crate::utils::path::resolve_server_file;
+
+/// VIEWING AND EDITING level.dat
+
+const DIFFICULTIES: [&str; 4] = ["peaceful", "easy", "normal", "hard"];
+
+// Spawn coordinates the game accepts (world border and maximum build limits)
+const MAX_HORIZONTAL: i32 = 29_999_984;
+const MIN_Y: i32 = -2032;
+const MAX_Y: i32 = 2031;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LevelDat {
+    pub world: String,
+    pub level_name: Option<String>,
+
+    // Strings, JavaScript numbers can't hold a 64-bit seed
+    pub seed: Option<String>,
+
+    pub spawn_x: i32,
+    pub spawn_y: i32,
+    pub spawn_z: i32,
+
+    pub difficulty: Option<String>,
+    pub difficulty_locked: bool,
+    pub hardcore: bool,
+
+    pub game_rules: BTreeMap<String, String>,
+
+    pub time: i64,
+    pub day_time: i64,
+
+    pub raining: bool,
+    pub rain_time: i32,
+    pub thundering: bool,
+    pub thunder_time: i32,
+    pub clear_weather_time: i32,
+
+    pub data_version: Option<i32>,
+    pub version_name: Option<String>,
+}
+
+/// Only the fields that are `Some` are written.
+///
+/// The data version is read-only: it has to agree with `Data/Version` and every chunk,
+/// and the game decides from it which upgrades to run on the world.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct LevelDatUpdate {
+    pub seed: Option<String>,
+    pub spawn_x: Option<i32>,
+    pub spawn_y: Option<i32>,
+    pub spawn_z: Option<i32>,
+    pub difficulty: Option<String>,
+    pub difficulty_locked: Option<bool>,
+    pub hardcore: Option<bool>,
+    pub game_rules: Option<BTreeMap<String, String>>,
+    pub time: Option<i64>,
+    pub day_time: Option<i64>,
+    pub raining: Option<bool>,
+    pub rain_time: Option<i32>,
+    pub thundering: Option<bool>,
+    pub thunder_time: Option<i32>,
+    pub clear_weather_time: Option<i32>,
+}
+
+fn level_dat_path(server_path: &str, world: &str) -> Result<PathBuf, String> {
+    let path = resolve_server_file(server_path, &format!("{}/level.dat", world))?;
+
+    if !path.exists() {
+        return Err(format!("{} has no level.dat", world));
+    }
+
+    Ok(path)
+}
+
+fn int_at(data: &Tag, key: &str) -> Option<i64> {
+    data.get(key).and_then(Tag::as_i64)
+}
+
+fn flag(data: &Tag, key: &str) -> bool {
+    int_at(data, key).unwrap_or(0) != 0
+}
+
+// 1.16+ keeps the seed in WorldGenSettings, older worlds in RandomSeed
+const SEED_PATHS: [&str; 2] = ["WorldGenSettings/seed", "RandomSeed"];
+
+// Spawn is a `spawn: { pos: [I; x, y, z] }` compound since 1.21.9, separate ints before
+pub fn read_spawn(data: &Tag) -> (i32, i32, i32) {
+    if let Some(Tag::IntArray(pos)) = data.get_path("spawn/pos") {
+        if let [x, y, z] = pos[..] {
+            return (x, y, z);
+        }
+    }
+
+    (
+        int_at(data, "SpawnX").unwrap_or(0) as i32,
+        int_at(data, "SpawnY").unwrap_or(64) as i32,
+        int_at(data, "SpawnZ").unwrap_or(0) as i32,
+    )
+}
+
+fn tag_to_rule_value(tag: &Tag) -> Option<String> {
+    match tag {
+        Tag::String(v) => Some(v.clone()),
+        Tag::Byte(v) => Some((*v != 0).to_string()),
+        other => other.as_i64().map(|v| v.to_string()),
+    }
+}
+
+fn read_level(world: &str, data: &Tag) -> LevelDat {
+    let game_rules = match data.get("GameRules").or_else(|| data.get("game_rules")) {
+        Some(Tag::Compound(entries)) => entries
+            .iter()
+            .filter_map(|(k, v)| tag_to_rule_value(v).map(|v| (k.clone(), v)))
+            .collect(),
+        _ => BTreeMap::new(),
+    };
+
+    let (spawn_x, spawn_y, spawn_z) = read_spawn(data);
+
+    LevelDat {
+        world: world.to_string(),
+        level_name: data
+            .get("LevelName")
+            .and_then(Tag::as_str)
+            .map(String::from),
+        seed: SEED_PATHS
+            .iter()
+            .find_map(|p| data.get_path(p))
+            .and_then(Tag::as_i64)
+            .map(|s| s.to_string()),
+        spawn_x,
+        spawn_y,
+        spawn_z,
+        difficulty: int_at(data, "Difficulty")
+            .and_then(|d| DIFFICULTIES.get(d as usize))
+            .map(|d| d.to_string()),
+        difficulty_locked: flag(data, "DifficultyLocked"),
+        hardcore: flag(data, "hardcore"),
+        game_rules,
+        time: int_at(data, "Time").unwrap_or(0),
+        day_time: int_at(data, "DayTime").unwrap_or(0),
+        raining: flag(data, "raining"),
+        rain_time: int_at(data, "rainTime").unwrap_or(0) as i32,
+        thundering: flag(data, "thundering"),
+        thunder_time: int_at(data, "thunderTime").unwrap_or(0) as i32,
+        clear_weather_time: int_at(data, "clearWeatherTime").unwrap_or(0) as i32,
+        data_version: int_at(data, "DataVersion").map(|v| v as i32),
+        version_name: data
+            .get_path("Version/Name")
+            .and_then(Tag::as_str)
+            .map(String::from),
+    }
+}
+
+#[tauri::command]
+pub fn read_level_dat(server_id: String, world: Option<String>) -> Result<LevelDat, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+
+    let file = nbt::read_file(&level_dat_path(&server.path, &world)?)?;
+    let data = file
+        .root
+        .get("Data")
+        .ok_or("level.dat has no Data compound")?;
+
+    Ok(read_level(&world, data))
+}
+
+/// Same as the game: numbers are used as-is, any other text is hashed like Java's String.hashCode
+pub fn parse_seed(seed: &str) -> Result<i64, String> {
+    let seed = seed.trim();
+
+    if seed.is_empty() {
+        return Err("Seed can't be empty".into());
+    }
+
+    if let Ok(n) = seed.parse::<i64>() {
+        return Ok(n);
+    }
+
+    let hash = seed
+        .encode_utf16()
+        .fold(0i32, |h, c| h.wrapping_mul(31).wrapping_add(c as i32));
+
+    Ok(hash as i64)
+}
+
+// Keeps the tag type the world already uses for a rule (strings before 1.21.11, typed after)
+fn rule_tag(existing: &Tag, value: &str) -> Result<Tag, String> {
+    let current = tag_to_rule_value(existing).unwrap_or_default();
+    let is_bool = current == "true" || current == "false";
+
+    if is_bool && value != "true" && value != "false" {
+        return Err("must be true or false".into());
+    }
+
+    if !is_bool && value.parse::<i32>().is_err() {
+        return Err("must be a whole number".into());
+    }
+
+    Ok(match existing {
+        Tag::String(_) => Tag::String(value.to_string()),
+        Tag::Byte(_) => Tag::Byte((value == "true") as i8),
+        _ => Tag::Int(value.parse().unwrap_or(0)),
+    })
+}
+
+fn set_int(data: &mut Tag, key: &str, value: i32) {
+    data.insert(key, Tag::Int(value));
+}
+
+fn set_flag(data: &mut Tag, key: &str, value: bool) {
+    data.insert(key, Tag::Byte(value as i8));
+}
+
+/// Validates every field first and only then applies them, so a bad value never
+/// leaves a half-edited level.dat behind.
+fn apply_update(data: &mut Tag, update: &LevelDatUpdate) -> Result<(), String> {
+    let mut errors = Vec::new();
+
+    let seed = match &update.seed {
+        Some(seed) => parse_seed(seed).map_err(|e| errors.push(e)).ok(),
+        None => None,
+    };
+
+    for (axis, value) in [("X", update.spawn_x), ("Z", update.spawn_z)] {
+        if value.is_some_and(|v| !(-MAX_HORIZONTAL..=MAX_HORIZONTAL).contains(&v)) {
+            errors.push(format!(
+                "Spawn {} must be between -{} and {}",
+                axis, MAX_HORIZONTAL, MAX_HORIZONTAL
+            ));
+        }
+    }
+
+    if update
+        .spawn_y
+        .is_some_and(|y| !(MIN_Y..=MAX_Y).contains(&y))
+    {
+        errors.push(format!("Spawn Y must be between {} and {}", MIN_Y, MAX_Y));
+    }
+
+    let difficulty = match &update.difficulty {
+        Some(d) => match DIFFICULTIES.iter().position(|x| x.eq_ignore_ascii_case(d)) {
+            Some(i) => Some(i as i8),
+            None => {
+                errors.push(format!("Unknown difficulty {}", d));
+                None
+            }
+        },
+        None => None,
+    };
+
+    let rules_key = if data.get("GameRules").is_some() {
+        "GameRules"
+    } else {
+        "game_rules"
+    };
+
+    let mut rules = Vec::new();
+    if let Some(updated) = &update.game_rules {
+        for (name, value) in updated {
+            match data.get(rules_key).and_then(|r| r.get(name)) {
+                Some(existing) => match rule_tag(existing, value.trim()) {
+                    Ok(tag) => rules.push((name.clone(), tag)),
+                    Err(e) => errors.push(format!("Game rule {} {}", name, e)),
+                },
+                None => errors.push(format!("Unknown game rule {}", name)),
+            }
+        }
+    }
+
+    for (name, value) in [("Time", update.time), ("Day time", update.day_time)] {
+        if value.is_some_and(|v| v < 0) {
+            errors.push(format!("{} can't be negative", name));
+        }
+    }
+
+    for (name, value) in [
+        ("Rain time", update.rain_time),
+        ("Thunder time", update.thunder_time),
+        ("Clear weather time", update.clear_weather_time),
+    ] {
+        if value.is_some_and(|v| v < 0) {
+            errors.push(format!("{} can't be negative", name));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    // Everything is valid, apply
+    if let Some(seed) = seed {
+        let mut written = false;
+        for path in SEED_PATHS {
+            if let Some(tag) = data.get_path_mut(path) {
+                *tag = Tag::Long(seed);
+                written = true;
+            }
+        }
+        if !written {
+            data.insert("RandomSeed", Tag::Long(seed));
+        }
+    }
+
+    let new_style_spawn = matches!(data.get_path("spawn/pos"), Some(Tag::IntArray(_)));
+    if new_style_spawn {
+        if let Some(Tag::IntArray(pos)) = data.get_path_mut("spawn/pos") {
+            for (i, value) in [update.spawn_x, update.spawn_y, update.spawn_z]
+                .into_iter()
+                .enumerate()
+            {
+                if let (Some(v), Some(slot)) = (value, pos.get_mut(i)) {
+                    *slot = v;
+                }
+            }
+        }
+    } else {
+        for (key, value) in [
+            ("SpawnX", update.spawn_x),
+            ("SpawnY", update.spawn_y),
+            ("SpawnZ", update.spawn_z),
+        ] {
+            if let Some(v) = value {
+                set_int(data, key, v);
+            }
+        }
+    }
+
+    if let Some(d) = difficulty {
+        data.insert("Difficulty", Tag::Byte(d));
+    }
+
+    if let Some(locked) = update.difficulty_locked {
+        set_flag(data, "DifficultyLocked", locked);
+    }
+
+    if let Some(hardcore) = update.hardcore {
+        set_flag(data, "hardcore", hardcore);
+    }
+
+    if let Some(game_rules) = data.get_mut(rules_key) {
+        for (name, tag) in rules {
+            game_rules.insert(&name, tag);
+        }
+    }
+
+    if let Some(time) = update.time {
+        data.insert("Time", Tag::Long(time));
+    }
+
+    if let Some(day_time) = update.day_time {
+        data.insert("DayTime", Tag::Long(day_time));
+    }
+
+    if let Some(raining) = update.raining {
+        set_flag(data, "raining", raining);
+    }
+
+    if let Some(thundering) = update.thundering {
+        set_flag(data, "thundering", thundering);
+    }
+
+    for (key, value) in [
+        ("rainTime", update.rain_time),
+        ("thunderTime", update.thunder_time),
+        ("clearWeatherTime", update.clear_weather_time),
+    ] {
+        if let Some(v) = value {
+            set_int(data, key, v);
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn update_level_dat(
+    server_id: String,
+    world: Option<String>,
+    update: LevelDatUpdate,
+    state: tauri::State<'_, AppState>,
+) -> Result<LevelDat, String> {
+    // The server rewrites level.dat on shutdown, edits made while it runs would be lost
+    if is_server_running(&state, &server_id) {
+        return Err("Stop the server before editing level.dat".into());
+    }
+
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let path = level_dat_path(&server.path, &world)?;
+
+    let mut file = nbt::read_file(&path)?;
+    let data = file
+        .root
+        .get_mut("Data")
+        .ok_or("level.dat has no Data compound")?;
+
+    apply_update(data, &update)?;
+    let result = read_level(&world, data);
+
+    // Keep the previous version where the game itself looks for it
+    fs::copy(&path, path.with_file_name("level.dat_old"))
+        .map_err(|e| format!("Failed to back up level.dat: {}", e))?;
+
+    nbt::write_file(&path, &file)?;
+
+    Ok(result)
+}
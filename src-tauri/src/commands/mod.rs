@@ -1,9 +1,28 @@
+pub mod backup_manager;
+pub mod datapacks;
 pub mod discord_rpc;
+pub mod eula;
 pub mod java_manager;
+pub mod level_editor;
 pub mod misc;
+pub mod nbt_viewer;
+pub mod motd;
 pub mod ngrok_manager;
+pub mod player_data;
+pub mod player_stats;
+pub mod players;
 pub mod playit_manager;
+pub mod properties_schema;
+pub mod remote_backups;
+pub mod resource_packs;
 pub mod server_creation;
+pub mod server_icon;
+pub mod server_launch;
 pub mod server_management;
 pub mod system;
 pub mod versions_loaders;
+pub mod world_analysis;
+pub mod world_export;
+pub mod world_import;
+pub mod world_manager;
+pub mod world_trim;
@@ -0,0 +1,18 @@
+use crate::utils::motd::{parse_motd, spans_to_legacy, Motd, MotdSpan};
+
+/// PREVIEWING AND BUILDING THE MOTD
+
+// The supported servers (vanilla and the Fabric / Forge ones built on it) send the motd
+// key as written, a JSON text component would show up as raw text.
+
+/// Styled spans of a MOTD exactly as the multiplayer list renders them, with warnings.
+#[tauri::command]
+pub fn preview_motd(motd: String) -> Motd {
+    parse_motd(&motd, false)
+}
+
+/// Turns edited spans back into the § coded text stored in server.properties.
+#[tauri::command]
+pub fn build_motd(spans: Vec<MotdSpan>) -> String {
+    spans_to_legacy(&spans)
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::commands::server_management::{is_server_running, load_server_config};
+use crate::nbt::{
+    self,
+    json::{from_json, to_json},
+    snbt::to_snbt,
+    Compression, NbtFile, Tag,
+};
+use crate::state::app_state::AppState;
+use crate::utils::path::resolve_server_file;
+
+/// READING AND WRITING NBT FILES OF A SERVER (level.dat, playerdata, data/*.dat...)
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NbtDocument {
+    pub name: String,
+    pub compression: Compression,
+    pub root: Value,
+}
+
+#[tauri::command]
+pub fn read_nbt_file(server_path: String, file: String) -> Result<NbtDocument, String> {
+    let path = resolve_server_file(&server_path, &file)?;
+    let nbt = nbt::read_file(&path)?;
+
+    Ok(NbtDocument {
+        name: nbt.name,
+        compression: nbt.compression,
+        root: to_json(&nbt.root),
+    })
+}
+
+#[tauri::command]
+pub fn read_nbt_file_as_snbt(server_path: String, file: String) -> Result<String, String> {
+    let path = resolve_server_file(&server_path, &file)?;
+    Ok(to_snbt(&nbt::read_file(&path)?.root))
+}
+
+/// Writes a JSON tree, as returned by `read_nbt_file` and edited by the frontend, back
+/// into an existing NBT file. Every value is checked against its type first.
+#[tauri::command]
+pub fn write_nbt_file(
+    server_path: String,
+    file: String,
+    document: NbtDocument,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    // The server keeps most of these files in memory and would overwrite the edit
+    if let Ok(server) = load_server_config(&server_path) {
+        if is_server_running(&state, &server.id) {
+            return Err("Stop the server before editing its files".into());
+        }
+    }
+
+    let path = resolve_server_file(&server_path, &file)?;
+
+    if !path.is_file() {
+        return Err(format!("{} does not exist", file));
+    }
+
+    let root = from_json(&document.root)?;
+
+    if !matches!(root, Tag::Compound(_)) {
+        return Err("The root of an NBT file must be a compound".into());
+    }
+
+    nbt::write_file(
+        &path,
+        &NbtFile {
+            name: document.name,
+            root,
+            compression: document.compression,
+        },
+    )
+}
@@ -136,11 +136,14 @@ pub fn ngrok_installed(base: &PathBuf) -> bool {
 }
 
 /// Start Ngrok
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::process::{Child, Stdio};
 use std::thread::sleep;
 use std::time::Duration;
 
+// The agent's local API, plain HTTP
+const NGROK_API: &str = "http://127.0.0.1:4040/api/tunnels";
+
 pub async fn start_ngrok(port: u16, base: &PathBuf) -> Result<(Child, String), String> {
     let bin = ngrok_binary(base);
 
@@ -153,17 +156,44 @@ pub async fn start_ngrok(port: u16, base: &PathBuf) -> Result<(Child, String), S
     // Give ngrok time to boot
     sleep(Duration::from_secs(2));
 
-    let tunnels: Value = reqwest::get("https://127.0.0.1:4040/api/tunnels")
+    let tunnels: Value = reqwest::get(NGROK_API)
         .await
         .map_err(|_| "Failed to connect to ngrok API".to_string())?
         .json()
         .await
         .map_err(|_| "Failed to parse ngrok API response".to_string())?;
 
-    let url = tunnels["tunnels"][0]["publci_url"]
+    let url = tunnels["tunnels"][0]["public_url"]
         .as_str()
         .ok_or("No ngrok tunnel found")?
         .to_string();
 
     Ok((child, url))
 }
+
+/// Opens an HTTP tunnel to a local port in the running ngrok session, next to the game's
+/// TCP tunnel. Returns its public URL, the tunnel closes with the session.
+pub async fn start_ngrok_http(port: u16, name: &str) -> Result<String, String> {
+    let tunnel: Value = Client::new()
+        .post(NGROK_API)
+        .json(&json!({
+            "name": name,
+            "proto": "http",
+            "addr": port.to_string(),
+        }))
+        .send()
+        .await
+        .map_err(|_| "Failed to connect to ngrok API".to_string())?
+        .json()
+        .await
+        .map_err(|_| "Failed to parse ngrok API response".to_string())?;
+
+    tunnel["public_url"]
+        .as_str()
+        .map(String::from)
+        .ok_or(format!(
+            "ngrok didn't open a tunnel for port {}: {}",
+            port,
+            tunnel["msg"].as_str().unwrap_or("no reason given")
+        ))
+}
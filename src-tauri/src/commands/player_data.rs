@@ -0,0 +1,563 @@
+use std::{collections::HashSet, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::players::{normalize_uuid, read_user_cache, resolve_player};
+use crate::commands::server_management::{find_server, is_server_running, level_name};
+use crate::nbt::{
+    self,
+    snbt::{parse_snbt, to_snbt},
+    Tag, TAG_COMPOUND,
+};
+use crate::state::app_state::AppState;
+use crate::utils::path::resolve_server_file;
+
+/// VIEWING AND EDITING PLAYER DATA (world/playerdata/<uuid>.dat)
+
+const GAME_MODES: [&str; 4] = ["survival", "creative", "adventure", "spectator"];
+
+// Data versions where the item and equipment formats changed
+const ITEM_COMPONENTS_VERSION: i64 = 3837; // 1.20.5: `count` + `components`
+const EQUIPMENT_VERSION: i64 = 4325; // 1.21.5: armor and offhand moved to `equipment`
+
+// Legacy slot numbers, also used for `equipment` so the frontend sees one layout
+const EQUIPMENT_SLOTS: [(&str, i32); 5] = [
+    ("feet", 100),
+    ("legs", 101),
+    ("chest", 102),
+    ("head", 103),
+    ("offhand", -106),
+];
+
+const MAX_HORIZONTAL: f64 = 30_000_000.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemStack {
+    pub slot: i32,
+    pub id: String,
+    pub count: i32,
+    // SNBT of the item's components (1.20.5+) or tag (older), kept as-is when editing
+    #[serde(default)]
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerData {
+    pub uuid: String,
+    pub name: Option<String>,
+
+    // 0-8 hotbar, 9-35 main inventory, 100-103 boots to helmet, -106 offhand
+    pub inventory: Vec<ItemStack>,
+    pub ender_chest: Vec<ItemStack>,
+
+    pub xp_level: i32,
+    pub xp_progress: f32,
+    pub xp_total: i32,
+
+    pub pos: [f64; 3],
+    pub dimension: String,
+    pub game_mode: String,
+
+    pub data_version: Option<i32>,
+}
+
+/// Only the fields that are `Some` are written.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PlayerDataUpdate {
+    pub inventory: Option<Vec<ItemStack>>,
+    pub ender_chest: Option<Vec<ItemStack>>,
+    pub xp_level: Option<i32>,
+    pub xp_progress: Option<f32>,
+    pub xp_total: Option<i32>,
+    pub pos: Option<[f64; 3]>,
+    pub dimension: Option<String>,
+    pub game_mode: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerSummary {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+fn player_file(server_path: &str, world: &str, uuid: &str) -> Result<PathBuf, String> {
+    resolve_server_file(server_path, &format!("{}/playerdata/{}.dat", world, uuid))
+}
+
+fn data_version(root: &Tag) -> i64 {
+    root.get("DataVersion").and_then(Tag::as_i64).unwrap_or(0)
+}
+
+fn read_item(item: &Tag, slot: i32) -> Option<ItemStack> {
+    let id = item.get("id")?.as_str()?.to_string();
+
+    let count = item
+        .get("count")
+        .or_else(|| item.get("Count"))
+        .and_then(Tag::as_i64)
+        .unwrap_or(1) as i32;
+
+    let data = item
+        .get("components")
+        .or_else(|| item.get("tag"))
+        .map(to_snbt);
+
+    Some(ItemStack {
+        slot,
+        id,
+        count,
+        data,
+    })
+}
+
+fn read_items(list: Option<&Tag>) -> Vec<ItemStack> {
+    list.and_then(Tag::as_list)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let slot = item.get("Slot").and_then(Tag::as_i64)? as i32;
+                    read_item(item, slot)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_dimension(root: &Tag) -> String {
+    match root.get("Dimension") {
+        Some(Tag::String(d)) => d.clone(),
+        // Before 1.16 dimensions were numbers
+        Some(tag) => match tag.as_i64() {
+            Some(-1) => "minecraft:the_nether".into(),
+            Some(1) => "minecraft:the_end".into(),
+            _ => "minecraft:overworld".into(),
+        },
+        None => "minecraft:overworld".into(),
+    }
+}
+
+fn read_player(uuid: &str, name: Option<String>, root: &Tag) -> PlayerData {
+    let mut inventory = read_items(root.get("Inventory"));
+
+    if let Some(equipment) = root.get("equipment") {
+        for (key, slot) in EQUIPMENT_SLOTS {
+            if let Some(item) = equipment.get(key).and_then(|i| read_item(i, slot)) {
+                inventory.push(item);
+            }
+        }
+    }
+
+    let pos = root
+        .get("Pos")
+        .and_then(Tag::as_list)
+        .map(|p| {
+            let axis = |i: usize| p.get(i).and_then(Tag::as_f64).unwrap_or(0.0);
+            [axis(0), axis(1), axis(2)]
+        })
+        .unwrap_or_default();
+
+    PlayerData {
+        uuid: uuid.to_string(),
+        name,
+        inventory,
+        ender_chest: read_items(root.get("EnderItems")),
+        xp_level: root.get("XpLevel").and_then(Tag::as_i64).unwrap_or(0) as i32,
+        xp_progress: root.get("XpP").and_then(Tag::as_f64).unwrap_or(0.0) as f32,
+        xp_total: root.get("XpTotal").and_then(Tag::as_i64).unwrap_or(0) as i32,
+        pos,
+        dimension: read_dimension(root),
+        game_mode: root
+            .get("playerGameType")
+            .and_then(Tag::as_i64)
+            .and_then(|m| GAME_MODES.get(m as usize))
+            .unwrap_or(&"survival")
+            .to_string(),
+        data_version: root
+            .get("DataVersion")
+            .and_then(Tag::as_i64)
+            .map(|v| v as i32),
+    }
+}
+
+#[tauri::command]
+pub fn list_player_data(
+    server_id: String,
+    world: Option<String>,
+) -> Result<Vec<PlayerSummary>, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let dir = resolve_server_file(&server.path, &format!("{}/playerdata", world))?;
+
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let cache = read_user_cache(&server.path);
+    let mut players = Vec::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.extension().and_then(|e| e.to_str()) != Some("dat") {
+            continue;
+        }
+
+        let Some(uuid) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(normalize_uuid)
+        else {
+            continue;
+        };
+
+        let name = cache
+            .iter()
+            .find(|u| normalize_uuid(&u.uuid).as_deref() == Some(uuid.as_str()))
+            .map(|u| u.name.clone());
+
+        let last_modified = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+
+        players.push(PlayerSummary {
+            uuid,
+            name,
+            last_modified,
+        });
+    }
+
+    players.sort_by_key(|p| {
+        p.name
+            .clone()
+            .unwrap_or_else(|| p.uuid.clone())
+            .to_lowercase()
+    });
+
+    Ok(players)
+}
+
+/// `player` can be a name or a UUID.
+#[tauri::command]
+pub async fn read_player_data(
+    server_id: String,
+    player: String,
+    world: Option<String>,
+) -> Result<PlayerData, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let identity = resolve_player(&server.path, &player).await?;
+
+    let path = player_file(&server.path, &world, &identity.uuid)?;
+    if !path.exists() {
+        return Err(format!("{} has never joined {}", player, world));
+    }
+
+    let file = nbt::read_file(&path)?;
+
+    Ok(read_player(&identity.uuid, identity.name, &file.root))
+}
+
+fn normalize_item_id(id: &str) -> Result<String, String> {
+    let id = id.trim().to_lowercase();
+    let id = if id.contains(':') {
+        id
+    } else {
+        format!("minecraft:{}", id)
+    };
+
+    let (namespace, path) = id.split_once(':').unwrap_or_default();
+    let valid = |s: &str, extra: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c) || extra.contains(c))
+    };
+
+    if !valid(namespace, "") || !valid(path, "/") {
+        return Err(format!("{} is not a valid item id", id));
+    }
+
+    Ok(id)
+}
+
+// The item already in a slot of a saved list
+fn original_item(list: Option<&Tag>, slot: i32) -> Option<&Tag> {
+    list.and_then(Tag::as_list)?
+        .iter()
+        .find(|item| item.get("Slot").and_then(Tag::as_i64) == Some(slot as i64))
+}
+
+fn item_tag(item: &ItemStack, original: Option<&Tag>, version: i64) -> Result<Tag, String> {
+    let components = version >= ITEM_COMPONENTS_VERSION;
+    let max_count = if components { 99 } else { 64 };
+
+    if !(1..=max_count).contains(&item.count) {
+        return Err(format!(
+            "Slot {}: count must be between 1 and {}",
+            item.slot, max_count
+        ));
+    }
+
+    let id = normalize_item_id(&item.id)?;
+
+    // Edit the item that was there so keys the editor doesn't show (Damage, ...) survive,
+    // a different item starts from scratch
+    let mut tag = match original {
+        Some(o) if o.get("id").and_then(Tag::as_str) == Some(id.as_str()) => o.clone(),
+        _ => Tag::Compound(Vec::new()),
+    };
+
+    tag.insert("id", Tag::String(id));
+
+    if components {
+        tag.insert("count", Tag::Int(item.count));
+    } else {
+        tag.insert("Count", Tag::Byte(item.count as i8));
+    }
+
+    let data_key = if components { "components" } else { "tag" };
+
+    match item.data.as_deref().filter(|d| !d.trim().is_empty()) {
+        Some(data) => {
+            let data = parse_snbt(data)
+                .map_err(|e| format!("Slot {}: invalid item data: {}", item.slot, e))?;
+
+            if data.id() != TAG_COMPOUND {
+                return Err(format!("Slot {}: item data must be a compound", item.slot));
+            }
+
+            tag.insert(data_key, data);
+        }
+        None => {
+            tag.remove(data_key);
+        }
+    }
+
+    Ok(tag)
+}
+
+fn items_list(items: &[&ItemStack], original: Option<&Tag>, version: i64) -> Result<Tag, String> {
+    let items = items
+        .iter()
+        .map(|item| {
+            let mut tag = item_tag(item, original_item(original, item.slot), version)?;
+            // An item kept from the list already has its (same) slot
+            if let Tag::Compound(entries) = &mut tag {
+                if !entries.iter().any(|(k, _)| k == "Slot") {
+                    entries.insert(0, ("Slot".into(), Tag::Byte(item.slot as i8)));
+                }
+            }
+            Ok(tag)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(Tag::List {
+        element: TAG_COMPOUND,
+        items,
+    })
+}
+
+fn check_slots(items: &[ItemStack], valid: &dyn Fn(i32) -> bool, what: &str) -> Result<(), String> {
+    let mut seen = HashSet::new();
+
+    for item in items {
+        if !valid(item.slot) {
+            return Err(format!("{} slot {} doesn't exist", what, item.slot));
+        }
+        if !seen.insert(item.slot) {
+            return Err(format!("{} slot {} is used twice", what, item.slot));
+        }
+    }
+
+    Ok(())
+}
+
+fn set_inventory(root: &mut Tag, items: &[ItemStack], version: i64) -> Result<(), String> {
+    check_slots(
+        items,
+        &|s| (0..=35).contains(&s) || (100..=103).contains(&s) || s == -106,
+        "Inventory",
+    )?;
+
+    let uses_equipment = root.get("equipment").is_some() || version >= EQUIPMENT_VERSION;
+
+    let (equipped, stored): (Vec<&ItemStack>, Vec<&ItemStack>) = items
+        .iter()
+        .partition(|i| uses_equipment && EQUIPMENT_SLOTS.iter().any(|(_, s)| *s == i.slot));
+
+    let inventory = items_list(&stored, root.get("Inventory"), version)?;
+
+    if uses_equipment {
+        // Keep slots we don't manage (like `body` and `saddle`) as they are
+        let mut equipment = root
+            .get("equipment")
+            .cloned()
+            .unwrap_or(Tag::Compound(Vec::new()));
+
+        for (key, slot) in EQUIPMENT_SLOTS {
+            match equipped.iter().find(|i| i.slot == slot) {
+                Some(item) => {
+                    let tag = item_tag(item, equipment.get(key), version)?;
+                    equipment.insert(key, tag);
+                }
+                None => {
+                    equipment.remove(key);
+                }
+            }
+        }
+
+        root.insert("equipment", equipment);
+    }
+
+    root.insert("Inventory", inventory);
+
+    Ok(())
+}
+
+fn set_dimension(root: &mut Tag, dimension: &str) -> Result<(), String> {
+    let dimension = dimension.trim();
+
+    if !dimension.contains(':') {
+        return Err(format!("{} is not a valid dimension id", dimension));
+    }
+
+    if matches!(root.get("Dimension"), Some(Tag::String(_)) | None) {
+        root.insert("Dimension", Tag::String(dimension.to_string()));
+        return Ok(());
+    }
+
+    let legacy = match dimension {
+        "minecraft:overworld" => 0,
+        "minecraft:the_nether" => -1,
+        "minecraft:the_end" => 1,
+        _ => return Err("This world version only supports the vanilla dimensions".into()),
+    };
+
+    root.insert("Dimension", Tag::Int(legacy));
+
+    Ok(())
+}
+
+fn apply_update(root: &mut Tag, update: &PlayerDataUpdate) -> Result<(), String> {
+    let version = data_version(root);
+
+    if update.xp_level.is_some_and(|v| v < 0) || update.xp_total.is_some_and(|v| v < 0) {
+        return Err("Experience can't be negative".into());
+    }
+
+    if update
+        .xp_progress
+        .is_some_and(|p| !(0.0..=1.0).contains(&p))
+    {
+        return Err("Experience progress must be between 0 and 1".into());
+    }
+
+    if let Some(pos) = update.pos {
+        if pos.iter().any(|v| !v.is_finite())
+            || pos[0].abs() > MAX_HORIZONTAL
+            || pos[2].abs() > MAX_HORIZONTAL
+        {
+            return Err("Position is outside of the world".into());
+        }
+    }
+
+    let game_mode = match &update.game_mode {
+        Some(m) => Some(
+            GAME_MODES
+                .iter()
+                .position(|x| x.eq_ignore_ascii_case(m))
+                .ok_or(format!("Unknown game mode {}", m))?,
+        ),
+        None => None,
+    };
+
+    // Work on a copy so a failing field leaves the file untouched
+    let mut edited = root.clone();
+
+    if let Some(items) = &update.inventory {
+        set_inventory(&mut edited, items, version)?;
+    }
+
+    if let Some(items) = &update.ender_chest {
+        check_slots(items, &|s| (0..=26).contains(&s), "Ender chest")?;
+        edited.insert(
+            "EnderItems",
+            items_list(
+                &items.iter().collect::<Vec<_>>(),
+                root.get("EnderItems"),
+                version,
+            )?,
+        );
+    }
+
+    if let Some(level) = update.xp_level {
+        edited.insert("XpLevel", Tag::Int(level));
+    }
+
+    if let Some(progress) = update.xp_progress {
+        edited.insert("XpP", Tag::Float(progress));
+    }
+
+    if let Some(total) = update.xp_total {
+        edited.insert("XpTotal", Tag::Int(total));
+    }
+
+    if let Some(pos) = update.pos {
+        edited.insert(
+            "Pos",
+            Tag::List {
+                element: nbt::TAG_DOUBLE,
+                items: pos.iter().map(|v| Tag::Double(*v)).collect(),
+            },
+        );
+    }
+
+    if let Some(dimension) = &update.dimension {
+        set_dimension(&mut edited, dimension)?;
+    }
+
+    if let Some(mode) = game_mode {
+        edited.insert("playerGameType", Tag::Int(mode as i32));
+    }
+
+    *root = edited;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn update_player_data(
+    server_id: String,
+    player: String,
+    world: Option<String>,
+    update: PlayerDataUpdate,
+    state: tauri::State<'_, AppState>,
+) -> Result<PlayerData, String> {
+    // The server saves players on shutdown and would overwrite the edit
+    if is_server_running(&state, &server_id) {
+        return Err("Stop the server before editing player data".into());
+    }
+
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let identity = resolve_player(&server.path, &player).await?;
+
+    let path = player_file(&server.path, &world, &identity.uuid)?;
+    if !path.exists() {
+        return Err(format!("{} has never joined {}", player, world));
+    }
+
+    let mut file = nbt::read_file(&path)?;
+    apply_update(&mut file.root, &update)?;
+
+    // Same backup name the game uses
+    fs::copy(&path, path.with_extension("dat_old"))
+        .map_err(|e| format!("Failed to back up player data: {}", e))?;
+
+    nbt::write_file(&path, &file)?;
+
+    Ok(read_player(&identity.uuid, identity.name, &file.root))
+}
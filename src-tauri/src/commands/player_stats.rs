@@ -0,0 +1,276 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::commands::players::{normalize_uuid, read_user_cache, resolve_player};
+use crate::commands::server_management::{find_server, level_name};
+use crate::utils::path::resolve_server_file;
+
+/// PLAYER STATISTICS AND ADVANCEMENTS (world/stats, world/advancements)
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PlayerStats {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub play_time_ticks: u64,
+    pub deaths: u64,
+    pub mob_kills: u64,
+    pub blocks_mined: u64,
+    pub distance_cm: u64,
+    pub advancements_completed: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardStat {
+    PlayTime,
+    Deaths,
+    MobKills,
+    BlocksMined,
+    Distance,
+    Advancements,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub value: u64,
+}
+
+// Stats parsed from one stats file (the advancement count is cached separately)
+#[derive(Debug, Clone, Copy, Default)]
+struct StatTotals {
+    play_time_ticks: u64,
+    deaths: u64,
+    mob_kills: u64,
+    blocks_mined: u64,
+    distance_cm: u64,
+}
+
+// Parsed files by path, only reused while the file's modification time is unchanged
+static STATS_CACHE: Lazy<Mutex<HashMap<PathBuf, (SystemTime, StatTotals)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static ADVANCEMENTS_CACHE: Lazy<Mutex<HashMap<PathBuf, (SystemTime, u64)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cached<T: Copy + Default>(
+    cache: &Mutex<HashMap<PathBuf, (SystemTime, T)>>,
+    path: &Path,
+    parse: fn(&Value) -> T,
+) -> T {
+    let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) else {
+        return T::default();
+    };
+
+    if let Some((time, value)) = cache.lock().unwrap().get(path) {
+        if *time == modified {
+            return *value;
+        }
+    }
+
+    let value = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .map(|json| parse(&json))
+        .unwrap_or_default();
+
+    cache
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), (modified, value));
+
+    value
+}
+
+fn sum(values: Option<&Value>, filter: impl Fn(&str) -> bool) -> u64 {
+    values
+        .and_then(Value::as_object)
+        .map(|map| {
+            map.iter()
+                .filter(|(k, _)| filter(k))
+                .filter_map(|(_, v)| v.as_u64())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn parse_stats(json: &Value) -> StatTotals {
+    // 1.13+ nests stats by category, older versions use flat "stat.*" keys
+    if let Some(stats) = json.get("stats") {
+        let custom = stats.get("minecraft:custom");
+        let get = |key: &str| custom.and_then(|c| c.get(key)).and_then(Value::as_u64);
+
+        return StatTotals {
+            // Renamed from play_one_minute in 1.17
+            play_time_ticks: get("minecraft:play_time")
+                .or_else(|| get("minecraft:play_one_minute"))
+                .unwrap_or(0),
+            deaths: get("minecraft:deaths").unwrap_or(0),
+            mob_kills: get("minecraft:mob_kills").unwrap_or(0),
+            blocks_mined: sum(stats.get("minecraft:mined"), |_| true),
+            distance_cm: sum(custom, |k| k.ends_with("_one_cm")),
+        };
+    }
+
+    let get = |key: &str| json.get(key).and_then(Value::as_u64).unwrap_or(0);
+
+    StatTotals {
+        play_time_ticks: get("stat.playOneMinute"),
+        deaths: get("stat.deaths"),
+        mob_kills: get("stat.mobKills"),
+        blocks_mined: sum(Some(json), |k| k.starts_with("stat.mineBlock.")),
+        distance_cm: sum(Some(json), |k| {
+            k.starts_with("stat.") && k.ends_with("OneCm")
+        }),
+    }
+}
+
+fn parse_advancements(json: &Value) -> u64 {
+    json.as_object()
+        .map(|map| {
+            map.iter()
+                // Unlocked recipes are stored as advancements too
+                .filter(|(k, _)| !k.contains(":recipes/"))
+                .filter(|(_, v)| v.get("done").and_then(Value::as_bool) == Some(true))
+                .count() as u64
+        })
+        .unwrap_or(0)
+}
+
+fn player_stats(world_dir: &Path, uuid: &str, name: Option<String>) -> PlayerStats {
+    let totals = cached(
+        &STATS_CACHE,
+        &world_dir.join("stats").join(format!("{}.json", uuid)),
+        parse_stats,
+    );
+
+    let advancements_completed = cached(
+        &ADVANCEMENTS_CACHE,
+        &world_dir
+            .join("advancements")
+            .join(format!("{}.json", uuid)),
+        parse_advancements,
+    );
+
+    PlayerStats {
+        uuid: uuid.to_string(),
+        name,
+        play_time_ticks: totals.play_time_ticks,
+        deaths: totals.deaths,
+        mob_kills: totals.mob_kills,
+        blocks_mined: totals.blocks_mined,
+        distance_cm: totals.distance_cm,
+        advancements_completed,
+    }
+}
+
+/// `player` can be a name or a UUID.
+#[tauri::command]
+pub async fn get_player_stats(
+    server_id: String,
+    player: String,
+    world: Option<String>,
+) -> Result<PlayerStats, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let world_dir = resolve_server_file(&server.path, &world)?;
+    let identity = resolve_player(&server.path, &player).await?;
+
+    Ok(player_stats(&world_dir, &identity.uuid, identity.name))
+}
+
+fn rank_players(
+    server_path: &str,
+    world_dir: &Path,
+    stat: LeaderboardStat,
+) -> Vec<LeaderboardEntry> {
+    let mut uuids = Vec::new();
+    for folder in ["stats", "advancements"] {
+        let Ok(entries) = fs::read_dir(world_dir.join(folder)) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            if let Some(uuid) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(normalize_uuid)
+            {
+                if !uuids.contains(&uuid) {
+                    uuids.push(uuid);
+                }
+            }
+        }
+    }
+
+    let cache = read_user_cache(server_path);
+
+    let mut entries: Vec<LeaderboardEntry> = uuids
+        .into_iter()
+        .map(|uuid| {
+            let name = cache
+                .iter()
+                .find(|u| normalize_uuid(&u.uuid).as_deref() == Some(uuid.as_str()))
+                .map(|u| u.name.clone());
+
+            let stats = player_stats(world_dir, &uuid, name);
+
+            let value = match stat {
+                LeaderboardStat::PlayTime => stats.play_time_ticks,
+                LeaderboardStat::Deaths => stats.deaths,
+                LeaderboardStat::MobKills => stats.mob_kills,
+                LeaderboardStat::BlocksMined => stats.blocks_mined,
+                LeaderboardStat::Distance => stats.distance_cm,
+                LeaderboardStat::Advancements => stats.advancements_completed,
+            };
+
+            LeaderboardEntry {
+                uuid: stats.uuid,
+                name: stats.name,
+                value,
+            }
+        })
+        .collect();
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.value));
+    entries
+}
+
+/// Ranks every player with a stats or advancements file, highest first.
+#[tauri::command]
+pub async fn get_stats_leaderboard(
+    server_id: String,
+    stat: LeaderboardStat,
+    world: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LeaderboardEntry>, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let world_dir = resolve_server_file(&server.path, &world)?;
+
+    // Reads a file or two per player, too slow for the async runtime on big servers
+    let mut entries =
+        tokio::task::spawn_blocking(move || rank_players(&server.path, &world_dir, stat))
+            .await
+            .map_err(|e| e.to_string())?;
+
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,146 @@
+use std::{fs, path::PathBuf};
+
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::commands::server_management::{find_server, map_server_properties};
+
+/// RESOLVING PLAYER NAMES AND UUIDS
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlayerIdentity {
+    pub uuid: String, // hyphenated, lowercase
+    pub name: Option<String>,
+    pub source: String, // "usercache", "mojang" or "offline"
+}
+
+// One entry of the server's usercache.json
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedUser {
+    pub name: String,
+    pub uuid: String,
+}
+
+/// Players the server has seen, from its usercache.json. Empty when the file is missing.
+pub fn read_user_cache(server_path: &str) -> Vec<CachedUser> {
+    fs::read_to_string(PathBuf::from(server_path).join("usercache.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Normalizes a UUID with or without hyphens to the hyphenated form used for file names.
+pub fn normalize_uuid(value: &str) -> Option<String> {
+    Uuid::parse_str(value.trim())
+        .ok()
+        .map(|u| u.hyphenated().to_string())
+}
+
+/// The UUID an offline-mode server gives a player, same as Java's
+/// `UUID.nameUUIDFromBytes("OfflinePlayer:" + name)`.
+pub fn offline_uuid(name: &str) -> String {
+    let mut hash: [u8; 16] = Md5::digest(format!("OfflinePlayer:{}", name).as_bytes()).into();
+
+    hash[6] = (hash[6] & 0x0f) | 0x30; // version 3
+    hash[8] = (hash[8] & 0x3f) | 0x80; // IETF variant
+
+    Uuid::from_bytes(hash).hyphenated().to_string()
+}
+
+fn is_valid_name(name: &str) -> bool {
+    (1..=16).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+async fn lookup_mojang(name: &str) -> Result<Option<PlayerIdentity>, String> {
+    let resp = reqwest::get(format!(
+        "https://api.mojang.com/users/profiles/minecraft/{}",
+        name
+    ))
+    .await
+    .map_err(|e| format!("Failed to reach Mojang: {}", e))?;
+
+    // Unknown names answer with 404 (204 on older API versions)
+    if resp.status() == reqwest::StatusCode::NOT_FOUND
+        || resp.status() == reqwest::StatusCode::NO_CONTENT
+    {
+        return Ok(None);
+    }
+
+    if !resp.status().is_success() {
+        return Err(format!("Mojang returned {}", resp.status()));
+    }
+
+    let profile: Value = resp.json().await.map_err(|e| e.to_string())?;
+
+    Ok(profile["id"]
+        .as_str()
+        .and_then(normalize_uuid)
+        .map(|uuid| PlayerIdentity {
+            uuid,
+            name: profile["name"].as_str().map(String::from),
+            source: "mojang".into(),
+        }))
+}
+
+/// Resolves a player name or UUID for a server.
+///
+/// The server's usercache.json is checked first. Unknown names are looked up at
+/// Mojang for online-mode servers, offline-mode servers use the offline UUID.
+pub async fn resolve_player(server_path: &str, player: &str) -> Result<PlayerIdentity, String> {
+    let player = player.trim();
+    let cache = read_user_cache(server_path);
+
+    if let Some(uuid) = normalize_uuid(player) {
+        let name = cache
+            .iter()
+            .find(|u| normalize_uuid(&u.uuid).as_deref() == Some(uuid.as_str()))
+            .map(|u| u.name.clone());
+
+        return Ok(PlayerIdentity {
+            uuid,
+            name,
+            source: "usercache".into(),
+        });
+    }
+
+    if !is_valid_name(player) {
+        return Err(format!("{} is not a valid player name or UUID", player));
+    }
+
+    if let Some(cached) = cache.iter().find(|u| u.name.eq_ignore_ascii_case(player)) {
+        if let Some(uuid) = normalize_uuid(&cached.uuid) {
+            return Ok(PlayerIdentity {
+                uuid,
+                name: Some(cached.name.clone()),
+                source: "usercache".into(),
+            });
+        }
+    }
+
+    let online_mode = map_server_properties(&server_path.to_string())
+        .ok()
+        .and_then(|map| map.get("online-mode").cloned());
+
+    if online_mode.as_deref().map(str::trim) == Some("false") {
+        return Ok(PlayerIdentity {
+            uuid: offline_uuid(player),
+            name: Some(player.to_string()),
+            source: "offline".into(),
+        });
+    }
+
+    lookup_mojang(player)
+        .await?
+        .ok_or(format!("No Minecraft account is named {}", player))
+}
+
+#[tauri::command]
+pub async fn resolve_player_uuid(
+    server_id: String,
+    player: String,
+) -> Result<PlayerIdentity, String> {
+    let server = find_server(&server_id)?;
+    resolve_player(&server.path, &player).await
+}
@@ -0,0 +1,932 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::backup_manager::send_if_running;
+use crate::commands::server_management::{load_server_config, map_server_properties};
+use crate::state::app_state::AppState;
+
+/// SCHEMA OF THE VANILLA server.properties KEYS, PER MINECRAFT VERSION
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyKind {
+    Boolean,
+    Integer,
+    Text,
+    Choice, // one of `allowed`
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PropertySchema {
+    pub key: &'static str,
+    pub kind: PropertyKind,
+    pub default: &'static str,
+    pub allowed: &'static [&'static str],
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub description: &'static str,
+    // false when Cubely can apply it to a running server with a command
+    pub restart_required: bool,
+    pub since: Option<&'static str>, // first version reading it
+    pub until: Option<&'static str>, // first version ignoring it
+    pub replaced_by: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    Unknown,
+    Deprecated,
+    Invalid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PropertyIssue {
+    pub key: String,
+    pub kind: IssueKind,
+    pub message: String,
+}
+
+const fn prop(key: &'static str, kind: PropertyKind, default: &'static str) -> PropertySchema {
+    PropertySchema {
+        key,
+        kind,
+        default,
+        allowed: &[],
+        min: None,
+        max: None,
+        description: "",
+        restart_required: true,
+        since: None,
+        until: None,
+        replaced_by: None,
+    }
+}
+
+const fn flag(
+    key: &'static str,
+    default: &'static str,
+    description: &'static str,
+) -> PropertySchema {
+    PropertySchema {
+        description,
+        ..prop(key, PropertyKind::Boolean, default)
+    }
+}
+
+const fn text(
+    key: &'static str,
+    default: &'static str,
+    description: &'static str,
+) -> PropertySchema {
+    PropertySchema {
+        description,
+        ..prop(key, PropertyKind::Text, default)
+    }
+}
+
+const fn int(
+    key: &'static str,
+    default: &'static str,
+    min: i64,
+    max: i64,
+    description: &'static str,
+) -> PropertySchema {
+    PropertySchema {
+        description,
+        min: Some(min),
+        max: Some(max),
+        ..prop(key, PropertyKind::Integer, default)
+    }
+}
+
+const fn choice(
+    key: &'static str,
+    default: &'static str,
+    allowed: &'static [&'static str],
+    description: &'static str,
+) -> PropertySchema {
+    PropertySchema {
+        description,
+        allowed,
+        ..prop(key, PropertyKind::Choice, default)
+    }
+}
+
+const fn since(schema: PropertySchema, version: &'static str) -> PropertySchema {
+    PropertySchema {
+        since: Some(version),
+        ..schema
+    }
+}
+
+const fn until(
+    schema: PropertySchema,
+    version: &'static str,
+    replaced_by: Option<&'static str>,
+) -> PropertySchema {
+    PropertySchema {
+        until: Some(version),
+        replaced_by,
+        ..schema
+    }
+}
+
+const fn live(schema: PropertySchema) -> PropertySchema {
+    PropertySchema {
+        restart_required: false,
+        ..schema
+    }
+}
+
+const PORT_MAX: i64 = 65535;
+const INT_MAX: i64 = i32::MAX as i64;
+
+pub static SCHEMA: &[PropertySchema] = &[
+    since(
+        flag(
+            "accepts-transfers",
+            "false",
+            "Accept players transferred from another server with /transfer",
+        ),
+        "1.20.5",
+    ),
+    flag(
+        "allow-flight",
+        "false",
+        "Don't kick players that fly in survival (needed by some mods)",
+    ),
+    until(
+        flag(
+            "allow-nether",
+            "true",
+            "Allow players to travel to the Nether",
+        ),
+        "1.21.9",
+        Some("the allowEnteringNetherUsingPortals game rule"),
+    ),
+    until(
+        flag(
+            "announce-player-achievements",
+            "true",
+            "Announce achievements in chat",
+        ),
+        "1.12",
+        Some("the announceAdvancements game rule"),
+    ),
+    flag(
+        "broadcast-console-to-ops",
+        "true",
+        "Send console command output to online operators",
+    ),
+    flag(
+        "broadcast-rcon-to-ops",
+        "true",
+        "Send RCON command output to online operators",
+    ),
+    since(
+        text(
+            "bug-report-link",
+            "",
+            "Link shown to players in the pause menu to report server problems",
+        ),
+        "1.21",
+    ),
+    live(choice(
+        "difficulty",
+        "easy",
+        &["peaceful", "easy", "normal", "hard"],
+        "World difficulty",
+    )),
+    flag(
+        "enable-command-block",
+        "false",
+        "Allow command blocks to run",
+    ),
+    since(
+        flag(
+            "enable-jmx-monitoring",
+            "false",
+            "Expose tick time metrics through JMX",
+        ),
+        "1.16",
+    ),
+    flag(
+        "enable-query",
+        "false",
+        "Answer GameSpy4 query requests (server lists)",
+    ),
+    flag("enable-rcon", "false", "Allow remote console access"),
+    since(
+        flag(
+            "enable-status",
+            "true",
+            "Show the server as online in the multiplayer list",
+        ),
+        "1.16",
+    ),
+    since(
+        flag(
+            "enforce-secure-profile",
+            "true",
+            "Only allow players with a Mojang-signed chat key",
+        ),
+        "1.19",
+    ),
+    flag(
+        "enforce-whitelist",
+        "false",
+        "Kick players that aren't whitelisted when the whitelist reloads",
+    ),
+    since(
+        int(
+            "entity-broadcast-range-percentage",
+            "100",
+            10,
+            1000,
+            "How far away entities are sent to players, in percent",
+        ),
+        "1.16",
+    ),
+    flag(
+        "force-gamemode",
+        "false",
+        "Put players in the default game mode every time they join",
+    ),
+    since(
+        int(
+            "function-permission-level",
+            "2",
+            1,
+            4,
+            "Permission level of functions from datapacks",
+        ),
+        "1.14.4",
+    ),
+    live(choice(
+        "gamemode",
+        "survival",
+        &["survival", "creative", "adventure", "spectator"],
+        "Default game mode of new players",
+    )),
+    flag(
+        "generate-structures",
+        "true",
+        "Generate villages, temples and other structures",
+    ),
+    text(
+        "generator-settings",
+        "{}",
+        "JSON settings of the world generator, used by flat worlds",
+    ),
+    flag(
+        "hardcore",
+        "false",
+        "Players are set to spectator when they die",
+    ),
+    since(
+        flag(
+            "hide-online-players",
+            "false",
+            "Don't show player names in the multiplayer list",
+        ),
+        "1.18",
+    ),
+    since(
+        text(
+            "initial-disabled-packs",
+            "",
+            "Datapacks disabled when the world is created, comma separated",
+        ),
+        "1.19.3",
+    ),
+    since(
+        text(
+            "initial-enabled-packs",
+            "vanilla",
+            "Datapacks enabled when the world is created, comma separated",
+        ),
+        "1.19.3",
+    ),
+    text("level-name", "world", "Folder of the world to load"),
+    text("level-seed", "", "Seed of new worlds, random when empty"),
+    text(
+        "level-type",
+        "minecraft:normal",
+        "World preset of new worlds (minecraft:normal, minecraft:flat...)",
+    ),
+    since(
+        flag("log-ips", "true", "Write player IP addresses in the log"),
+        "1.20.2",
+    ),
+    since(
+        flag(
+            "management-server-enabled",
+            "false",
+            "Enable the JSON-RPC management API",
+        ),
+        "1.21.9",
+    ),
+    since(
+        text(
+            "management-server-host",
+            "localhost",
+            "Address the management API listens on",
+        ),
+        "1.21.9",
+    ),
+    since(
+        int(
+            "management-server-port",
+            "0",
+            0,
+            PORT_MAX,
+            "Port of the management API",
+        ),
+        "1.21.9",
+    ),
+    since(
+        text(
+            "management-server-secret",
+            "",
+            "Token clients of the management API must send",
+        ),
+        "1.21.9",
+    ),
+    since(
+        flag(
+            "management-server-tls-enabled",
+            "true",
+            "Serve the management API over TLS",
+        ),
+        "1.21.9",
+    ),
+    until(
+        int(
+            "max-build-height",
+            "256",
+            64,
+            256,
+            "Highest block players can build at",
+        ),
+        "1.17",
+        None,
+    ),
+    since(
+        int(
+            "max-chained-neighbor-updates",
+            "1000000",
+            -1,
+            INT_MAX,
+            "Limit of chained block updates before the rest are skipped",
+        ),
+        "1.19",
+    ),
+    int(
+        "max-players",
+        "20",
+        0,
+        INT_MAX,
+        "Players that can be online at once",
+    ),
+    int(
+        "max-tick-time",
+        "60000",
+        -1,
+        i64::MAX,
+        "Milliseconds a tick may take before the watchdog stops the server, -1 disables it",
+    ),
+    int(
+        "max-world-size",
+        "29999984",
+        1,
+        29999984,
+        "Radius of the world border, in blocks",
+    ),
+    text(
+        "motd",
+        "A Minecraft Server",
+        "Message shown under the server name in the multiplayer list",
+    ),
+    int(
+        "network-compression-threshold",
+        "256",
+        -1,
+        INT_MAX,
+        "Packets larger than this are compressed, -1 disables compression",
+    ),
+    flag(
+        "online-mode",
+        "true",
+        "Check players against Mojang's servers, turn off only behind a proxy",
+    ),
+    int(
+        "op-permission-level",
+        "4",
+        0,
+        4,
+        "Permission level given by /op",
+    ),
+    since(
+        int(
+            "pause-when-empty-seconds",
+            "60",
+            -1,
+            INT_MAX,
+            "Stop ticking the world this long after the last player leaves, -1 never pauses",
+        ),
+        "1.21.2",
+    ),
+    int(
+        "player-idle-timeout",
+        "0",
+        0,
+        INT_MAX,
+        "Minutes before idle players are kicked, 0 disables it",
+    ),
+    flag(
+        "prevent-proxy-connections",
+        "false",
+        "Kick players whose IP doesn't match the one Mojang saw",
+    ),
+    until(
+        since(
+            flag(
+                "previews-chat",
+                "false",
+                "Show a preview of chat messages while they are typed",
+            ),
+            "1.19",
+        ),
+        "1.19.3",
+        None,
+    ),
+    until(
+        flag("pvp", "true", "Allow players to damage each other"),
+        "1.21.9",
+        Some("the pvp game rule"),
+    ),
+    int(
+        "query.port",
+        "25565",
+        1,
+        PORT_MAX,
+        "Port of the query protocol",
+    ),
+    int(
+        "rate-limit",
+        "0",
+        0,
+        INT_MAX,
+        "Packets per second before a player is kicked, 0 disables it",
+    ),
+    text("rcon.password", "", "Password of the remote console"),
+    int(
+        "rcon.port",
+        "25575",
+        1,
+        PORT_MAX,
+        "Port of the remote console",
+    ),
+    since(
+        choice(
+            "region-file-compression",
+            "deflate",
+            &["deflate", "lz4", "none"],
+            "Compression of newly written chunks",
+        ),
+        "1.20.5",
+    ),
+    since(
+        flag(
+            "require-resource-pack",
+            "false",
+            "Kick players that decline the resource pack",
+        ),
+        "1.17",
+    ),
+    text(
+        "resource-pack",
+        "",
+        "URL of the resource pack players download when joining",
+    ),
+    since(
+        text("resource-pack-id", "", "UUID identifying the resource pack"),
+        "1.20.3",
+    ),
+    since(
+        text(
+            "resource-pack-prompt",
+            "",
+            "Message shown when asking players to use the resource pack",
+        ),
+        "1.17",
+    ),
+    since(
+        text(
+            "resource-pack-sha1",
+            "",
+            "SHA-1 of the resource pack, so clients can verify and cache it",
+        ),
+        "1.8",
+    ),
+    text(
+        "server-ip",
+        "",
+        "Address to listen on, empty for all addresses",
+    ),
+    int(
+        "server-port",
+        "25565",
+        1,
+        PORT_MAX,
+        "Port players connect to",
+    ),
+    since(
+        int(
+            "simulation-distance",
+            "10",
+            3,
+            32,
+            "Chunks around players where entities and blocks are ticked",
+        ),
+        "1.18",
+    ),
+    until(
+        flag("snooper-enabled", "true", "Send usage data to Mojang"),
+        "1.18",
+        None,
+    ),
+    until(
+        flag("spawn-animals", "true", "Spawn animals"),
+        "1.21.2",
+        None,
+    ),
+    until(
+        flag("spawn-monsters", "true", "Spawn monsters"),
+        "1.21.9",
+        Some("the spawnMonsters game rule"),
+    ),
+    until(
+        flag("spawn-npcs", "true", "Spawn villagers"),
+        "1.21.2",
+        None,
+    ),
+    int(
+        "spawn-protection",
+        "16",
+        0,
+        INT_MAX,
+        "Radius around spawn only operators can build in, 0 disables it",
+    ),
+    since(
+        int(
+            "status-heartbeat-interval",
+            "0",
+            0,
+            INT_MAX,
+            "Seconds between status notifications of the management API, 0 disables them",
+        ),
+        "1.21.9",
+    ),
+    since(
+        flag(
+            "sync-chunk-writes",
+            "true",
+            "Write chunks synchronously, safer but slower",
+        ),
+        "1.16",
+    ),
+    since(
+        text(
+            "text-filtering-config",
+            "",
+            "Config of the chat text filter",
+        ),
+        "1.17",
+    ),
+    flag(
+        "use-native-transport",
+        "true",
+        "Use the faster Linux networking when available",
+    ),
+    int(
+        "view-distance",
+        "10",
+        3,
+        32,
+        "Chunks around players that are sent to them",
+    ),
+    live(flag(
+        "white-list",
+        "false",
+        "Only let players on the whitelist join",
+    )),
+];
+
+// "1.20.4" -> [1, 20, 4], "26.1" -> [26, 1]. Snapshots (24w14a) give None
+fn version_parts(version: &str) -> Option<Vec<u32>> {
+    // Pre-releases and release candidates count as the release they lead to
+    let release = version.split(['-', ' ']).next()?;
+    release.split('.').map(|p| p.parse().ok()).collect()
+}
+
+fn compare_versions(a: &[u32], b: &[u32]) -> Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+impl PropertySchema {
+    fn introduced(&self, version: Option<&[u32]>) -> bool {
+        match (self.since.and_then(version_parts), version) {
+            (Some(since), Some(version)) => compare_versions(version, &since).is_ge(),
+            _ => true,
+        }
+    }
+
+    // Like `introduced`, an unknown version (a snapshot) is neither filtered nor flagged
+    fn removed(&self, version: Option<&[u32]>) -> bool {
+        match (self.until.and_then(version_parts), version) {
+            (Some(until), Some(version)) => compare_versions(version, &until).is_ge(),
+            _ => false,
+        }
+    }
+
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match self.kind {
+            PropertyKind::Boolean => {
+                if value != "true" && value != "false" {
+                    return Err("must be true or false".into());
+                }
+            }
+            PropertyKind::Integer => {
+                let n: i64 = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| "must be a whole number".to_string())?;
+
+                if self.min.is_some_and(|min| n < min) || self.max.is_some_and(|max| n > max) {
+                    return Err(format!(
+                        "must be between {} and {}",
+                        self.min.unwrap_or(i64::MIN),
+                        self.max.unwrap_or(i64::MAX)
+                    ));
+                }
+            }
+            PropertyKind::Choice => {
+                // Difficulty and game mode are also accepted as numbers
+                let numeric = matches!(self.key, "difficulty" | "gamemode")
+                    && value.parse::<usize>().is_ok_and(|n| n < self.allowed.len());
+
+                if !self.allowed.contains(&value) && !numeric {
+                    return Err(format!("must be one of {}", self.allowed.join(", ")));
+                }
+            }
+            PropertyKind::Text => {}
+        }
+
+        if value.contains(['\n', '\r']) && self.key != "motd" {
+            return Err("can't contain line breaks".into());
+        }
+
+        Ok(())
+    }
+}
+
+pub fn find_schema(key: &str) -> Option<&'static PropertySchema> {
+    SCHEMA.iter().find(|s| s.key == key)
+}
+
+// Prefix of the error sent when values are rejected, followed by the issues as JSON
+pub const INVALID_PROPERTIES: &str = "INVALID_PROPERTIES";
+
+// What is wrong with one key for this version, None when it's fine or not a vanilla key
+fn property_issue(
+    schema: &PropertySchema,
+    value: &str,
+    parts: Option<&[u32]>,
+    version_name: &str,
+) -> Option<PropertyIssue> {
+    if schema.removed(parts) {
+        return Some(PropertyIssue {
+            key: schema.key.into(),
+            kind: IssueKind::Deprecated,
+            message: match schema.replaced_by {
+                Some(replacement) => format!(
+                    "Ignored since {}, use {} instead",
+                    schema.until.unwrap_or_default(),
+                    replacement
+                ),
+                None => format!("Ignored since {}", schema.until.unwrap_or_default()),
+            },
+        });
+    }
+
+    if !schema.introduced(parts) {
+        return Some(PropertyIssue {
+            key: schema.key.into(),
+            kind: IssueKind::Unknown,
+            message: format!(
+                "Only used from {}, {} ignores it",
+                schema.since.unwrap_or_default(),
+                version_name
+            ),
+        });
+    }
+
+    schema.validate(value).err().map(|e| PropertyIssue {
+        key: schema.key.into(),
+        kind: IssueKind::Invalid,
+        message: format!("{} {}", schema.key, e),
+    })
+}
+
+/// Checks values before they are written to a server of this `version`. Unknown keys are
+/// let through, mods and server software add their own.
+pub fn validate_properties(values: &[(&str, String)], version: Option<&str>) -> Vec<PropertyIssue> {
+    let parts = version.and_then(version_parts);
+    let version_name = version.unwrap_or("this version");
+
+    values
+        .iter()
+        .filter_map(|(key, value)| {
+            property_issue(find_schema(key)?, value, parts.as_deref(), version_name)
+        })
+        .collect()
+}
+
+/// Whether a vanilla key is read by this version, unknown keys always apply.
+pub fn applies_to_version(key: &str, version: Option<&str>) -> bool {
+    let parts = version.and_then(version_parts);
+    !find_schema(key)
+        .is_some_and(|s| !s.introduced(parts.as_deref()) || s.removed(parts.as_deref()))
+}
+
+pub fn issues_error(issues: &[PropertyIssue]) -> String {
+    format!(
+        "{}:{}",
+        INVALID_PROPERTIES,
+        serde_json::to_string(issues).unwrap_or_default()
+    )
+}
+
+/// Sends the commands that apply live properties to the server if it's running.
+pub fn apply_live_properties(state: &AppState, server_id: &str, values: &[(&str, String)]) {
+    for (key, value) in values {
+        // The commands only take names, "difficulty 0" is rejected by modern servers
+        let name = |key: &str| -> String {
+            let allowed = find_schema(key).map(|s| s.allowed).unwrap_or_default();
+            value
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| allowed.get(n))
+                .map(|n| n.to_string())
+                .unwrap_or(value.clone())
+        };
+
+        let command = match *key {
+            "difficulty" => format!("difficulty {}", name(key)),
+            "gamemode" => format!("defaultgamemode {}", name(key)),
+            "white-list" if value == "true" => "whitelist on".to_string(),
+            "white-list" => "whitelist off".to_string(),
+            _ => continue,
+        };
+
+        send_if_running(state, server_id, &command);
+    }
+}
+
+/// Keys that apply to the server's version.
+#[tauri::command]
+pub fn get_properties_schema(server_path: String) -> Result<Vec<PropertySchema>, String> {
+    let version = load_server_config(&server_path).ok().map(|c| c.version);
+    let parts = version.as_deref().and_then(version_parts);
+
+    Ok(SCHEMA
+        .iter()
+        .filter(|s| s.introduced(parts.as_deref()) && !s.removed(parts.as_deref()))
+        .cloned()
+        .collect())
+}
+
+/// Problems in the current server.properties: invalid values, and keys that this
+/// version doesn't know about or no longer uses.
+#[tauri::command]
+pub fn check_server_properties(server_path: String) -> Result<Vec<PropertyIssue>, String> {
+    let version = load_server_config(&server_path).ok().map(|c| c.version);
+    let parts = version.as_deref().and_then(version_parts);
+    let version_name = version.clone().unwrap_or("this version".into());
+
+    let map: HashMap<String, String> = map_server_properties(&server_path)?;
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+
+    let mut issues = Vec::new();
+
+    for key in keys {
+        let Some(schema) = find_schema(key) else {
+            issues.push(PropertyIssue {
+                key: key.clone(),
+                kind: IssueKind::Unknown,
+                message: "Not a vanilla property, it may belong to a mod or server software".into(),
+            });
+            continue;
+        };
+
+        issues.extend(property_issue(
+            schema,
+            &map[key],
+            parts.as_deref(),
+            &version_name,
+        ));
+    }
+
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(key: &str) -> &'static PropertySchema {
+        find_schema(key).unwrap()
+    }
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(version_parts("1.20.4"), Some(vec![1, 20, 4]));
+        assert_eq!(version_parts("26.1"), Some(vec![26, 1]));
+        assert_eq!(version_parts("1.21-pre1"), Some(vec![1, 21]));
+        assert_eq!(version_parts("1.20.5-rc2"), Some(vec![1, 20, 5]));
+        assert_eq!(version_parts("1.21 Pre-Release 1"), Some(vec![1, 21]));
+        assert_eq!(version_parts("24w14a"), None);
+        assert_eq!(version_parts(""), None);
+    }
+
+    #[test]
+    fn compares_versions() {
+        assert_eq!(compare_versions(&[1, 20], &[1, 20, 0]), Ordering::Equal);
+        assert_eq!(compare_versions(&[1, 20, 4], &[1, 20]), Ordering::Greater);
+        assert_eq!(compare_versions(&[1, 9], &[1, 10]), Ordering::Less);
+        assert_eq!(compare_versions(&[26, 1], &[1, 21, 11]), Ordering::Greater);
+    }
+
+    #[test]
+    fn validates_values() {
+        assert!(schema("pvp").validate("true").is_ok());
+        assert!(schema("pvp").validate("yes").is_err());
+
+        assert!(schema("max-players").validate(" 20").is_ok());
+        assert!(schema("max-players").validate("-1").is_err());
+        assert!(schema("max-players").validate("2147483648").is_err());
+        assert!(schema("max-players").validate("many").is_err());
+
+        assert!(schema("difficulty").validate("hard").is_ok());
+        assert!(schema("difficulty").validate("3").is_ok());
+        assert!(schema("difficulty").validate("4").is_err());
+        assert!(schema("difficulty").validate("Hard").is_err());
+
+        // Only the MOTD may span lines
+        assert!(schema("motd").validate("one\ntwo").is_ok());
+        assert!(schema("level-seed").validate("one\ntwo").is_err());
+    }
+
+    #[test]
+    fn applies_by_version() {
+        // Added in 1.20.5
+        assert!(!applies_to_version("accepts-transfers", Some("1.20.4")));
+        assert!(applies_to_version("accepts-transfers", Some("1.20.5")));
+        assert!(applies_to_version("accepts-transfers", Some("1.21-pre1")));
+
+        // Removed in 1.12
+        let removed = "announce-player-achievements";
+        assert!(applies_to_version(removed, Some("1.11.2")));
+        assert!(!applies_to_version(removed, Some("1.12")));
+
+        // Unknown versions and keys are let through
+        for version in [None, Some("24w14a")] {
+            assert!(applies_to_version("accepts-transfers", version));
+            assert!(applies_to_version(removed, version));
+        }
+        assert!(applies_to_version("some-mod-key", Some("1.8")));
+    }
+
+    #[test]
+    fn unknown_versions_are_not_flagged() {
+        let values = [("announce-player-achievements", "true".to_string())];
+
+        assert!(validate_properties(&values, Some("24w14a")).is_empty());
+        assert!(validate_properties(&values, None).is_empty());
+        assert!(matches!(
+            validate_properties(&values, Some("1.20.4"))[..],
+            [PropertyIssue {
+                kind: IssueKind::Deprecated,
+                ..
+            }]
+        ));
+    }
+}
@@ -0,0 +1,443 @@
+use std::{
+    fs,
+    io::{self, Read},
+    net::{SocketAddr, UdpSocket},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use tiny_http::{Header, Method, Response, Server};
+use zip::ZipArchive;
+
+use crate::commands::datapacks::server_pack_format;
+use crate::commands::ngrok_manager::start_ngrok_http;
+use crate::commands::server_management::{
+    find_server, is_server_running, save_server_config, set_server_properties, ServerConfig,
+    TunnelProvider,
+};
+use crate::state::app_state::AppState;
+use crate::utils::pack_meta::{parse_pack_meta, PackMeta};
+
+/// HOSTING A RESOURCE PACK FOR THE SERVER (resource-pack in server.properties)
+
+// Not used by anything Minecraft related, players' clients download from here
+pub const DEFAULT_PACK_PORT: u16 = 25580;
+
+// Hosted packs are kept with the server, so backups include them
+const PACKS_DIR: &str = "resourcepacks";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HostedResourcePack {
+    pub file: String, // inside <server>/resourcepacks
+    pub sha1: String,
+    pub port: u16,
+    // Address players reach the port through, like a tunnel pointing at it. [Ex: example.gl.joinmc.link:12345]
+    pub public_address: Option<String>,
+    pub required: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct HostPackOptions {
+    pub port: Option<u16>,
+    pub public_address: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourcePackStatus {
+    pub pack: HostedResourcePack,
+    pub url: String,
+    pub size_bytes: u64,
+    pub meta: Option<PackMeta>,
+    pub warning: Option<String>,
+    pub serving: bool,
+    // Set after a change while the server runs, server.properties is only read on start
+    pub restart_required: bool,
+}
+
+// The built-in HTTP server, one per app, serving the pack of the last server that asked for it
+pub struct PackHost {
+    pub port: u16,
+    pub server: Arc<Server>,
+    pub server_id: String,
+    // (url path, file)
+    pub route: Arc<Mutex<(String, PathBuf)>>,
+    // The port's own ngrok tunnel, opened when the server starts
+    pub tunnel_url: Option<String>,
+}
+
+impl Drop for PackHost {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+fn sha1_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha1::new();
+    let mut buf = [0u8; 64 * 1024];
+
+    loop {
+        let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+// Resource packs must have pack.mcmeta at the root of the zip, the client ignores nested ones
+fn read_pack_meta(path: &Path) -> Result<PackMeta, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|_| "Not a valid zip file".to_string())?;
+
+    let mut content = String::new();
+    archive
+        .by_name("pack.mcmeta")
+        .map_err(|_| "pack.mcmeta must be at the root of the zip, not inside a folder".to_string())?
+        .read_to_string(&mut content)
+        .map_err(|e| e.to_string())?;
+
+    parse_pack_meta(&content)
+}
+
+// Best guess of this machine's LAN address, used when no public address is given
+fn local_address() -> Option<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    // Nothing is sent, connecting only picks the outgoing interface
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip().to_string())
+}
+
+/// The URL players download the pack from, through `public_address` when set, otherwise
+/// the LAN address. Only ngrok tunnels the pack port by itself (see `start_pack_hosting`).
+/// Behind playit, players outside the network need a tunnel or port forward for the pack
+/// port set as `public_address`.
+pub fn pack_url(pack: &HostedResourcePack) -> String {
+    let (scheme, address) = match &pack.public_address {
+        Some(address) if !address.trim().is_empty() => {
+            let address = address.trim().trim_end_matches('/');
+
+            match address.strip_prefix("https://") {
+                Some(rest) => ("https", rest.to_string()),
+                None => ("http", address.trim_start_matches("http://").to_string()),
+            }
+        }
+        _ => (
+            "http",
+            format!(
+                "{}:{}",
+                local_address().unwrap_or("127.0.0.1".into()),
+                pack.port
+            ),
+        ),
+    };
+
+    format!("{}://{}/{}.zip", scheme, address, pack.sha1)
+}
+
+fn tunnel_pack_url(tunnel_url: &str, pack: &HostedResourcePack) -> String {
+    format!("{}/{}.zip", tunnel_url.trim_end_matches('/'), pack.sha1)
+}
+
+fn serve(server: Arc<Server>, route: Arc<Mutex<(String, PathBuf)>>) {
+    for request in server.incoming_requests() {
+        let route = route.clone();
+
+        // A client per thread, packs can be large and several players may join at once
+        thread::spawn(move || {
+            let (path, file) = route.lock().unwrap().clone();
+
+            if !matches!(request.method(), Method::Get | Method::Head) || request.url() != path {
+                let _ = request.respond(Response::empty(404));
+                return;
+            }
+
+            match fs::File::open(&file) {
+                Ok(f) => {
+                    let header =
+                        Header::from_bytes(&b"Content-Type"[..], &b"application/zip"[..]).unwrap();
+                    let _ = request.respond(Response::from_file(f).with_header(header));
+                }
+                Err(_) => {
+                    let _ = request.respond(Response::empty(404));
+                }
+            }
+        });
+    }
+}
+
+/// Starts serving a server's pack, reusing the running HTTP server when the port matches.
+pub fn resume_pack_hosting(
+    state: &AppState,
+    server: &ServerConfig,
+    pack: &HostedResourcePack,
+) -> Result<(), String> {
+    let file = PathBuf::from(&server.path).join(PACKS_DIR).join(&pack.file);
+    if !file.exists() {
+        return Err(format!("{} is missing", pack.file));
+    }
+
+    let route = (format!("/{}.zip", pack.sha1), file);
+    let mut host = state.pack_host.lock().unwrap();
+
+    if let Some(host) = host.as_mut().filter(|h| h.port == pack.port) {
+        *host.route.lock().unwrap() = route;
+        if host.server_id != server.id {
+            host.server_id = server.id.clone();
+            host.tunnel_url = None;
+        }
+        return Ok(());
+    }
+
+    // Dropping the old host frees its port
+    *host = None;
+
+    let server_http = Server::http(SocketAddr::from(([0, 0, 0, 0], pack.port)))
+        .map_err(|e| format!("Failed to listen on port {}: {}", pack.port, e))?;
+    let server_http = Arc::new(server_http);
+    let route = Arc::new(Mutex::new(route));
+
+    {
+        let server_http = server_http.clone();
+        let route = route.clone();
+        thread::spawn(move || serve(server_http, route));
+    }
+
+    *host = Some(PackHost {
+        port: pack.port,
+        server: server_http,
+        server_id: server.id.clone(),
+        route,
+        tunnel_url: None,
+    });
+
+    Ok(())
+}
+
+/// Serves the pack of a server that is about to start and points server.properties at it,
+/// so a changed LAN address or a new tunnel URL is what the server reads.
+///
+/// With an ngrok tunnel and no `public_address` the pack port gets an HTTP tunnel in the
+/// same ngrok session. If that fails the LAN URL is written and the error returned.
+pub async fn start_pack_hosting(
+    state: &AppState,
+    server: &ServerConfig,
+    pack: &HostedResourcePack,
+    ngrok_running: bool,
+) -> Result<(), String> {
+    resume_pack_hosting(state, server, pack)?;
+
+    let tunnel = match (&pack.public_address, ngrok_running) {
+        (None, true) => Some(start_ngrok_http(pack.port, "cubely-resource-pack").await),
+        _ => None,
+    };
+
+    let url = match &tunnel {
+        Some(Ok(tunnel_url)) => {
+            set_tunnel_url(state, &server.id, tunnel_url);
+            tunnel_pack_url(tunnel_url, pack)
+        }
+        _ => pack_url(pack),
+    };
+
+    set_server_properties(&server.path, &[("resource-pack", url)])?;
+
+    match tunnel {
+        Some(Err(e)) => Err(format!(
+            "Players outside your network can't download the pack: {}",
+            e
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn set_tunnel_url(state: &AppState, server_id: &str, tunnel_url: &str) {
+    let mut host = state.pack_host.lock().unwrap();
+    if let Some(host) = host.as_mut().filter(|h| h.server_id == server_id) {
+        host.tunnel_url = Some(tunnel_url.to_string());
+    }
+}
+
+/// Stops serving a server's pack, dropping the host closes its port.
+pub fn stop_pack_hosting(state: &AppState, server_id: &str) {
+    let mut host = state.pack_host.lock().unwrap();
+    if host.as_ref().is_some_and(|h| h.server_id == server_id) {
+        *host = None;
+    }
+}
+
+fn pack_status(
+    state: &AppState,
+    server: &ServerConfig,
+    pack: HostedResourcePack,
+) -> ResourcePackStatus {
+    let file = PathBuf::from(&server.path).join(PACKS_DIR).join(&pack.file);
+    let meta = read_pack_meta(&file).ok();
+
+    let warning = match (&meta, server_pack_format(&server.path, "resource")) {
+        (Some(meta), Some(format)) if !meta.supports(format) => Some(format!(
+            "Made for pack format {}, the server's version uses {}. Players may see a warning.",
+            meta.pack_format.or(meta.min_format).unwrap_or_default(),
+            format
+        )),
+        _ => None,
+    };
+
+    // ngrok tunnels the pack port itself when the server starts
+    let playit = server
+        .tunnel
+        .as_ref()
+        .is_some_and(|t| t.enabled && matches!(t.provider, TunnelProvider::Playit));
+    let warning = warning.or_else(|| {
+        (playit && pack.public_address.is_none()).then(|| {
+            format!(
+                "The playit tunnel only forwards the game port, players outside your network \
+                 can't download the pack. Add a playit tunnel for port {} and set its address \
+                 as the public address.",
+                pack.port
+            )
+        })
+    });
+
+    let (serving, tunnel_url) = state
+        .pack_host
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|h| h.server_id == server.id && h.port == pack.port)
+        .map_or((false, None), |h| (true, h.tunnel_url.clone()));
+
+    ResourcePackStatus {
+        url: match tunnel_url {
+            Some(tunnel_url) => tunnel_pack_url(&tunnel_url, &pack),
+            None => pack_url(&pack),
+        },
+        size_bytes: fs::metadata(&file).map(|m| m.len()).unwrap_or(0),
+        meta,
+        warning,
+        serving,
+        restart_required: false,
+        pack,
+    }
+}
+
+/// Copies a pack zip into the server, serves it and points server.properties at it.
+#[tauri::command]
+pub fn host_resource_pack(
+    server_id: String,
+    source: String,
+    options: HostPackOptions,
+    state: tauri::State<'_, AppState>,
+) -> Result<ResourcePackStatus, String> {
+    let mut server = find_server(&server_id)?;
+    let source = PathBuf::from(source);
+
+    read_pack_meta(&source)?;
+
+    let file_name = source
+        .file_name()
+        .ok_or("Invalid resource pack path")?
+        .to_string_lossy()
+        .to_string();
+
+    let dir = PathBuf::from(&server.path).join(PACKS_DIR);
+    let target = dir.join(&file_name);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    if source != target {
+        fs::copy(&source, &target).map_err(|e| format!("Failed to copy pack: {}", e))?;
+    }
+
+    let pack = HostedResourcePack {
+        sha1: sha1_file(&target)?,
+        file: file_name,
+        port: options.port.unwrap_or(DEFAULT_PACK_PORT),
+        public_address: options.public_address.filter(|a| !a.trim().is_empty()),
+        required: options.required,
+    };
+
+    // A stopped server's pack is served from its next start. The old pack stays on disk
+    // until replaced, in case the new one can't be served
+    let running = is_server_running(&state, &server.id);
+    if running {
+        resume_pack_hosting(&state, &server, &pack)?;
+    }
+
+    if let Some(old) = &server.resource_pack {
+        if old.file != pack.file {
+            fs::remove_file(dir.join(&old.file)).ok();
+        }
+    }
+
+    set_server_properties(
+        &server.path,
+        &[
+            ("resource-pack", pack_url(&pack)),
+            ("resource-pack-sha1", pack.sha1.clone()),
+            ("require-resource-pack", pack.required.to_string()),
+        ],
+    )?;
+
+    server.resource_pack = Some(pack.clone());
+    save_server_config(&server)?;
+
+    let mut status = pack_status(&state, &server, pack);
+    status.restart_required = running;
+
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn get_resource_pack(
+    server_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<ResourcePackStatus>, String> {
+    let server = find_server(&server_id)?;
+
+    Ok(server
+        .resource_pack
+        .clone()
+        .map(|pack| pack_status(&state, &server, pack)))
+}
+
+/// Stops hosting and clears the resource pack keys from server.properties.
+#[tauri::command]
+pub fn remove_resource_pack(
+    server_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut server = find_server(&server_id)?;
+
+    stop_pack_hosting(&state, &server.id);
+
+    if let Some(pack) = server.resource_pack.take() {
+        match fs::remove_file(PathBuf::from(&server.path).join(PACKS_DIR).join(&pack.file)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                return Err(format!("Failed to delete {}: {}", pack.file, e))
+            }
+            _ => {}
+        }
+    }
+
+    set_server_properties(
+        &server.path,
+        &[
+            ("resource-pack", String::new()),
+            ("resource-pack-sha1", String::new()),
+            ("require-resource-pack", "false".into()),
+        ],
+    )?;
+
+    save_server_config(&server)
+}
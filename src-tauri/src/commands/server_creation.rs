@@ -6,16 +6,32 @@ use serde::{ser, Deserialize, Serialize};
 use serde_json::Value;
 use std::f32::consts::E;
 use std::fmt::format;
-use std::process::Command;
+use std::io::BufRead;
+use std::path::Path;
+use std::process::{Command, Stdio};
 use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::commands::java_manager::JavaVersion;
 use crate::commands::server_management::{ServerConfig, TunnelConfig, TunnelProvider};
+use crate::state::app_state::AppState;
 use crate::utils::path::{cleanup_empty_parent_dir, cleanup_server_dir, servers_dir};
 
 #[derive(Deserialize, Debug)]
 struct VersionDetails {
     downloads: Downloads,
+    #[serde(rename = "javaVersion")]
+    java_version: Option<JavaVersionInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct JavaVersionInfo {
+    #[serde(rename = "majorVersion")]
+    major_version: u32,
 }
 
 #[derive(Deserialize, Debug)]
@@ -65,7 +81,15 @@ pub async fn create_server(
     version: String,
     loader: LoaderType,
     ram_gb: u8,
+    state: tauri::State<'_, AppState>,
 ) -> Result<CreateServerResult, String> {
+    let java_base = {
+        let guard = state.java_base_dir.lock().unwrap();
+        guard.clone().ok_or("Java base directory not initialized")?
+    };
+
+    let app = state.app_handle.lock().unwrap().clone();
+
     let mut server_path = servers_dir();
     server_path.push(&version);
     server_path.push(&name);
@@ -76,43 +100,71 @@ pub async fn create_server(
 
     fs::create_dir_all(&server_path).map_err(|e| e.to_string())?;
 
-    let result: Result<(), String> = async {
-        match loader {
-            LoaderType::Vanilla => {
-                create_vanilla_server(&name, &version, &server_path).await?;
-            }
-            LoaderType::Fabric => {
-                create_fabric_server(&version, &server_path).await?;
-                fs::create_dir_all(server_path.join("mods")).ok();
+    let result: Result<Option<u32>, String> = async {
+        let java_major = match loader {
+            LoaderType::Vanilla => create_vanilla_server(&name, &version, &server_path).await?,
+            // Modded servers still run the vanilla version's Java
+            _ => fetch_version_details(&Client::new(), &version)
+                .await
+                .ok()
+                .and_then(|d| d.java_version)
+                .map(|j| j.major_version),
+        };
+
+        if !matches!(loader, LoaderType::Vanilla) {
+            // Installers run on the same Java the server will
+            let java_version = match java_major {
+                Some(major) => JavaVersion::from_major(major),
+                None => JavaVersion::from_mc_version(&version),
+            };
+
+            if !java_version.java_installed(&java_base) {
+                emit_installer_log(
+                    &app,
+                    format!("[CUBELY] Installing Java {}", java_version.major()),
+                );
+                java_version.install(&java_base).await?;
             }
-            LoaderType::Forge => {
-                create_forge_server(&version, &server_path).await?;
-                fs::create_dir_all(server_path.join("mods")).ok();
+
+            let java = java_version.java_binary(&java_base);
+
+            match loader {
+                LoaderType::Vanilla => {}
+                LoaderType::Fabric => {
+                    create_fabric_server(&version, &server_path, &java, &app).await?;
+                }
+                LoaderType::Forge => {
+                    create_forge_server(&version, &server_path, &java, &app).await?;
+                }
             }
+
+            fs::create_dir_all(server_path.join("mods")).ok();
         }
 
-        // Write server.properties and eula only after successful install
+        // Write server.properties only after successful install, the EULA is accepted separately
         fs::write(
             server_path.join("server.properties"),
             DEFAULT_SERVER_PROPERTIES,
         )
         .map_err(|e| e.to_string())?;
-        fs::write(server_path.join("eula.txt"), "eula=true\n").map_err(|e| e.to_string())?;
 
-        Ok(())
+        Ok(java_major)
     }
     .await;
 
     // Rollback on failure
-    if let Err(err) = result {
-        cleanup_server_dir(&server_path);
+    let java_major = match result {
+        Ok(java_major) => java_major,
+        Err(err) => {
+            cleanup_server_dir(&server_path);
 
-        if let Some(version_dir) = server_path.parent() {
-            cleanup_empty_parent_dir(&version_dir.to_path_buf());
-        }
+            if let Some(version_dir) = server_path.parent() {
+                cleanup_empty_parent_dir(&version_dir.to_path_buf());
+            }
 
-        return Err(err);
-    }
+            return Err(err);
+        }
+    };
 
     let config = ServerConfig {
         id: Uuid::new_v4().to_string(),
@@ -126,6 +178,10 @@ pub async fn create_server(
             enabled: false,
             provider: TunnelProvider::Playit,
         }),
+        resource_pack: None,
+        eula: None,
+        launch: Default::default(),
+        java_major,
     };
 
     fs::write(
@@ -140,14 +196,8 @@ pub async fn create_server(
     })
 }
 
-pub async fn create_vanilla_server(
-    name: &str,
-    version: &str,
-    server_path: &PathBuf,
-) -> Result<(), String> {
+async fn fetch_version_details(client: &Client, version: &str) -> Result<VersionDetails, String> {
     // Fetch version manifest
-    let client = Client::new();
-
     let manifest: serde_json::Value = client
         .get("https://launchermeta.mojang.com/mc/game/version_manifest.json")
         .send()
@@ -167,14 +217,24 @@ pub async fn create_vanilla_server(
         .ok_or("Version not found")?;
 
     // Fetch version details
-    let details: VersionDetails = client
+    client
         .get(version_url)
         .send()
         .await
         .map_err(|e| e.to_string())?
         .json()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())
+}
+
+// Returns the Java major version Mojang lists for this version
+pub async fn create_vanilla_server(
+    name: &str,
+    version: &str,
+    server_path: &PathBuf,
+) -> Result<Option<u32>, String> {
+    let client = Client::new();
+    let details = fetch_version_details(&client, version).await?;
 
     println!("{:?}", details);
 
@@ -194,10 +254,15 @@ pub async fn create_vanilla_server(
 
     fs::write(&jar_path, bytes).map_err(|e| e.to_string())?;
 
-    Ok(())
+    Ok(details.java_version.map(|j| j.major_version))
 }
 
-pub async fn create_fabric_server(version: &str, server_path: &PathBuf) -> Result<(), String> {
+pub async fn create_fabric_server(
+    version: &str,
+    server_path: &PathBuf,
+    java: &Path,
+    app: &Option<AppHandle>,
+) -> Result<(), String> {
     let client = Client::new();
 
     // Fetch latest Fabric installer info
@@ -240,20 +305,23 @@ pub async fn create_fabric_server(version: &str, server_path: &PathBuf) -> Resul
         .map_err(|e| format!("Failed to write Fabric installer: {}", e))?;
 
     // Run Fabric installer
-    let status = Command::new("java")
-        .arg("-jar")
-        .arg("fabric-installer.jar")
-        .arg("server")
-        .arg("-mcversion")
-        .arg(version)
-        .arg("-downloadMinecraft")
-        .current_dir(server_path)
-        .status()
-        .map_err(|e| format!("Failed to run Fabric installer (is Java installed?): {}", e))?;
-
-    // java -jar fabric-installer.jar server \
-    //     -mcversion 1.21.1 \
-    //     -downloadMinecraft
+    emit_installer_log(app, "[CUBELY] Running the Fabric installer".into());
+
+    let status = run_installer(
+        java,
+        &[
+            "-jar",
+            "fabric-installer.jar",
+            "server",
+            "-mcversion",
+            version,
+            "-downloadMinecraft",
+        ],
+        server_path,
+        app,
+    )
+    .await
+    .map_err(|e| format!("Failed to run Fabric installer: {}", e))?;
 
     if !status.success() {
         return Err("Fabric installer failed".into());
@@ -264,7 +332,12 @@ pub async fn create_fabric_server(version: &str, server_path: &PathBuf) -> Resul
     Ok(())
 }
 
-pub async fn create_forge_server(version: &str, server_path: &PathBuf) -> Result<(), String> {
+pub async fn create_forge_server(
+    version: &str,
+    server_path: &PathBuf,
+    java: &Path,
+    app: &Option<AppHandle>,
+) -> Result<(), String> {
     let client = Client::new();
 
     // Resolve real Forge version
@@ -302,13 +375,19 @@ pub async fn create_forge_server(version: &str, server_path: &PathBuf) -> Result
         .map_err(|e| format!("Failed to write Forge installer: {}", e))?;
 
     // Run Forge installer
-    let status = Command::new("java")
-        .arg("-jar")
-        .arg("forge-installer.jar")
-        .arg("--installServer")
-        .current_dir(server_path)
-        .status()
-        .map_err(|e| format!("Failed to run Forge installer (is Java installed?): {}", e))?;
+    emit_installer_log(
+        app,
+        format!("[CUBELY] Running the Forge {} installer", forge_version),
+    );
+
+    let status = run_installer(
+        java,
+        &["-jar", "forge-installer.jar", "--installServer"],
+        server_path,
+        app,
+    )
+    .await
+    .map_err(|e| format!("Failed to run Forge installer: {}", e))?;
 
     if !status.success() {
         return Err("Forge installer failed".into());
@@ -319,6 +398,65 @@ pub async fn create_forge_server(version: &str, server_path: &PathBuf) -> Result
     Ok(())
 }
 
+fn emit_installer_log(app: &Option<AppHandle>, line: String) {
+    if let Some(app) = app {
+        let _ = app.emit("installer-log", line);
+    }
+}
+
+// Runs an installer jar on a blocking thread, sending its output to the frontend line by line
+async fn run_installer(
+    java: &Path,
+    args: &[&str],
+    dir: &Path,
+    app: &Option<AppHandle>,
+) -> Result<std::process::ExitStatus, String> {
+    let mut cmd = Command::new(java);
+
+    cmd.args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(0x08000000); // NO TERMINAL WINDOW
+    }
+
+    let app = app.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+
+        let stderr = child.stderr.take().map(|stderr| {
+            let app = app.clone();
+
+            std::thread::spawn(move || {
+                let reader = std::io::BufReader::new(stderr);
+                for line in reader.lines().map_while(Result::ok) {
+                    emit_installer_log(&app, format!("[ERR] {}", line));
+                }
+            })
+        });
+
+        if let Some(stdout) = child.stdout.take() {
+            let reader = std::io::BufReader::new(stdout);
+            for line in reader.lines().map_while(Result::ok) {
+                emit_installer_log(&app, line);
+            }
+        }
+
+        if let Some(handle) = stderr {
+            let _ = handle.join();
+        }
+
+        child.wait().map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 async fn resolve_latest_forge_build(version: &str) -> Result<String, String> {
     let text = reqwest::get(
         "https://maven.minecraftforge.net/net/minecraftforge/forge/maven-metadata.xml",
@@ -0,0 +1,62 @@
+use std::{fs, path::PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{imageops::FilterType, ImageFormat, ImageReader};
+
+use crate::commands::server_management::find_server;
+
+/// SERVER ICON (server-icon.png, shown in the multiplayer list)
+
+// The client rejects icons of any other size
+const ICON_SIZE: u32 = 64;
+
+pub fn read_server_icon(server_path: &str) -> Option<String> {
+    let bytes = fs::read(PathBuf::from(server_path).join("server-icon.png")).ok()?;
+    Some(format!("data:image/png;base64,{}", STANDARD.encode(bytes)))
+}
+
+/// Crops any PNG or JPEG to a centered square and scales it to the 64x64 PNG Minecraft expects.
+/// Returns the new icon as a data URL. A running server picks it up on its next start.
+#[tauri::command]
+pub fn set_server_icon(server_id: String, source: String) -> Result<String, String> {
+    let server = find_server(&server_id)?;
+
+    // Guess from the content, a .png may well be a renamed JPEG
+    let image = ImageReader::open(&source)
+        .map_err(|e| e.to_string())?
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .decode()
+        .map_err(|e| format!("Unsupported image: {}", e))?;
+
+    let side = image.width().min(image.height());
+    let icon = image
+        .crop_imm(
+            (image.width() - side) / 2,
+            (image.height() - side) / 2,
+            side,
+            side,
+        )
+        .resize_exact(ICON_SIZE, ICON_SIZE, FilterType::Lanczos3)
+        .to_rgba8();
+
+    icon.save_with_format(
+        PathBuf::from(&server.path).join("server-icon.png"),
+        ImageFormat::Png,
+    )
+    .map_err(|e| format!("Failed to save server icon: {}", e))?;
+
+    read_server_icon(&server.path).ok_or("Failed to read the new icon".into())
+}
+
+#[tauri::command]
+pub fn remove_server_icon(server_id: String) -> Result<(), String> {
+    let server = find_server(&server_id)?;
+    let path = PathBuf::from(&server.path).join("server-icon.png");
+
+    if path.exists() {
+        fs::remove_file(path).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,686 @@
+use std::collections::BTreeMap;
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::java_manager::JavaVersion;
+use crate::commands::server_creation::LoaderType;
+use crate::commands::server_management::{
+    find_server, save_server_config, server_jar_version_json, ServerConfig,
+};
+use crate::state::app_state::AppState;
+
+/// JVM MEMORY, ARGUMENTS AND ENVIRONMENT USED TO START A SERVER
+
+// Below this the server doesn't get through world generation
+pub const MIN_HEAP_MB: u32 = 512;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JvmPreset {
+    Aikar,     // G1 tuned for Minecraft, https://mcflags.emc.gs
+    Zgc,       // generational ZGC, low pauses on large heaps
+    LowMemory, // small heaps on small machines
+}
+
+impl JvmPreset {
+    pub fn name(self) -> &'static str {
+        match self {
+            JvmPreset::Aikar => "Aikar's flags",
+            JvmPreset::Zgc => "ZGC",
+            JvmPreset::LowMemory => "low memory",
+        }
+    }
+
+    // Oldest Java the preset is meant for, below it most of its flags would be dropped
+    pub fn min_java(self) -> u32 {
+        match self {
+            // Non-generational ZGC pauses longer than G1 on Minecraft's heaps
+            JvmPreset::Zgc => 21,
+            JvmPreset::Aikar | JvmPreset::LowMemory => 8,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LaunchConfig {
+    // Both fall back to ram_gb when unset
+    pub min_heap_mb: Option<u32>,
+    pub max_heap_mb: Option<u32>,
+    pub preset: Option<JvmPreset>, // its flags go before jvm_args
+    pub jvm_args: Vec<String>,     // before -jar [Ex: -XX:+UseG1GC]
+    pub server_args: Vec<String>,  // after nogui [Ex: --forceUpgrade]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Total physical memory of this machine in MB.
+#[cfg(target_os = "linux")]
+pub fn total_memory_mb() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+
+    meminfo
+        .lines()
+        .find_map(|l| l.strip_prefix("MemTotal:"))
+        .and_then(|v| v.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        .map(|kb| kb / 1024)
+}
+
+#[cfg(target_os = "macos")]
+pub fn total_memory_mb() -> Option<u64> {
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "hw.memsize"])
+        .output()
+        .ok()?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|bytes| bytes / 1024 / 1024)
+}
+
+#[cfg(target_os = "windows")]
+pub fn total_memory_mb() -> Option<u64> {
+    use windows_sys::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    let mut status: MEMORYSTATUSEX = unsafe { std::mem::zeroed() };
+    status.dwLength = std::mem::size_of::<MEMORYSTATUSEX>() as u32;
+
+    if unsafe { GlobalMemoryStatusEx(&mut status) } == 0 {
+        return None;
+    }
+
+    Some(status.ullTotalPhys / 1024 / 1024)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn total_memory_mb() -> Option<u64> {
+    None
+}
+
+/// (min, max) heap in MB.
+pub fn heap_mb(server: &ServerConfig) -> (u32, u32) {
+    let fallback = server.ram_gb as u32 * 1024;
+    let max = server.launch.max_heap_mb.unwrap_or(fallback);
+    let min = server.launch.min_heap_mb.unwrap_or(max);
+    (min, max)
+}
+
+pub fn validate_launch(server: &ServerConfig) -> Result<(), String> {
+    let (min, max) = heap_mb(server);
+
+    if max < MIN_HEAP_MB {
+        return Err(format!(
+            "Give the server at least {} MB of memory",
+            MIN_HEAP_MB
+        ));
+    }
+
+    if min > max {
+        return Err(format!(
+            "Minimum memory ({} MB) can't be more than the maximum ({} MB)",
+            min, max
+        ));
+    }
+
+    // Firmware and GPUs reserve a little, so a "16 GB" machine reports slightly less
+    if let Some(total) = total_memory_mb() {
+        if max as u64 > total.div_ceil(1024) * 1024 {
+            return Err(format!(
+                "Maximum memory ({} MB) is more than this computer has ({} MB)",
+                max, total
+            ));
+        }
+    }
+
+    for arg in &server.launch.jvm_args {
+        let arg = arg.trim();
+
+        if arg.is_empty() {
+            return Err("JVM arguments can't be empty".into());
+        }
+
+        // Memory has its own fields
+        if arg.starts_with("-Xmx") || arg.starts_with("-Xms") {
+            return Err(format!(
+                "Set memory with the memory fields instead of {}",
+                arg
+            ));
+        }
+
+        // A second -jar would replace the server's
+        if arg == "-jar" {
+            return Err("JVM arguments can't contain -jar".into());
+        }
+    }
+
+    if server
+        .launch
+        .server_args
+        .iter()
+        .any(|a| a.trim().is_empty())
+    {
+        return Err("Server arguments can't be empty".into());
+    }
+
+    if let Some(preset) = server.launch.preset {
+        let java = server_java(server).major();
+
+        if java < preset.min_java() {
+            return Err(format!(
+                "The {} preset needs Java {} or newer, this server runs on Java {}",
+                preset.name(),
+                preset.min_java(),
+                java
+            ));
+        }
+    }
+
+    for key in server.launch.env.keys() {
+        if key.trim().is_empty() || key.contains(['=', '\0']) {
+            return Err(format!("{} is not a valid environment variable name", key));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LaunchPreview {
+    pub java_version: u32,
+    pub args: Vec<String>,
+    pub command_line: String,
+    // Preset flags the server's Java doesn't support
+    pub dropped_flags: Vec<String>,
+}
+
+// (flag, first Java version that has it, first Java version that removed it)
+type PresetFlag = (String, u32, Option<u32>);
+
+fn preset_flags(preset: JvmPreset, max_heap_mb: u32) -> Vec<PresetFlag> {
+    let flag = |arg: &str| (arg.to_string(), 8, None);
+
+    match preset {
+        JvmPreset::Aikar => {
+            // Bigger heaps get a bigger young generation and regions
+            let large = max_heap_mb >= 12 * 1024;
+            let (new_size, max_new_size, region, reserve, occupancy) = if large {
+                (40, 50, "16M", 15, 20)
+            } else {
+                (30, 40, "8M", 20, 15)
+            };
+
+            vec![
+                flag("-XX:+UseG1GC"),
+                flag("-XX:+ParallelRefProcEnabled"),
+                flag("-XX:MaxGCPauseMillis=200"),
+                flag("-XX:+UnlockExperimentalVMOptions"),
+                flag("-XX:+DisableExplicitGC"),
+                flag("-XX:+AlwaysPreTouch"),
+                flag(&format!("-XX:G1NewSizePercent={}", new_size)),
+                flag(&format!("-XX:G1MaxNewSizePercent={}", max_new_size)),
+                flag(&format!("-XX:G1HeapRegionSize={}", region)),
+                flag(&format!("-XX:G1ReservePercent={}", reserve)),
+                flag("-XX:G1HeapWastePercent=5"),
+                flag("-XX:G1MixedGCCountTarget=4"),
+                flag(&format!("-XX:InitiatingHeapOccupancyPercent={}", occupancy)),
+                flag("-XX:G1MixedGCLiveThresholdPercent=90"),
+                ("-XX:G1RSetUpdatingPauseTimePercent=5".into(), 8, Some(20)),
+                flag("-XX:SurvivorRatio=32"),
+                flag("-XX:+PerfDisableSharedMem"),
+                flag("-XX:MaxTenuringThreshold=1"),
+                flag("-Dusing.aikars.flags=https://mcflags.emc.gs"),
+                flag("-Daikars.new.flags=true"),
+            ]
+        }
+        JvmPreset::Zgc => vec![
+            ("-XX:+UseZGC".into(), 15, None),
+            // Generational mode is opt-in on 21 and 22, the only mode since 24
+            ("-XX:+ZGenerational".into(), 21, Some(23)),
+            ("-XX:+AlwaysPreTouch".into(), 8, None),
+            ("-XX:+DisableExplicitGC".into(), 8, None),
+        ],
+        JvmPreset::LowMemory => vec![
+            flag("-XX:+UseSerialGC"),
+            flag("-XX:ReservedCodeCacheSize=64m"),
+            flag("-XX:MaxMetaspaceSize=256m"),
+            // Only G1 deduplicates strings before Java 18
+            ("-XX:+UseStringDeduplication".into(), 18, None),
+        ],
+    }
+}
+
+/// Splits a preset's flags into the ones this Java supports and the ones it doesn't.
+pub fn resolve_preset(
+    preset: JvmPreset,
+    java: JavaVersion,
+    max_heap_mb: u32,
+) -> (Vec<String>, Vec<String>) {
+    let major = java.major();
+    let (mut kept, mut dropped) = (Vec::new(), Vec::new());
+
+    for (arg, since, until) in preset_flags(preset, max_heap_mb) {
+        if major >= since && major < until.unwrap_or(u32::MAX) {
+            kept.push(arg);
+        } else {
+            dropped.push(arg);
+        }
+    }
+
+    (kept, dropped)
+}
+
+/// How an installed Forge server is started.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForgeLaunch {
+    // `-jar <file>`, the shim of 1.20.3+ or the jar of 1.16.5 and older
+    Jar(String),
+    // `@libraries/.../unix_args.txt` (win_args.txt on Windows) of 1.17 to 1.20.2
+    ArgsFile(String),
+}
+
+#[cfg(target_os = "windows")]
+const FORGE_ARGS_FILE: &str = "win_args.txt";
+#[cfg(not(target_os = "windows"))]
+const FORGE_ARGS_FILE: &str = "unix_args.txt";
+
+#[cfg(target_os = "windows")]
+const FORGE_RUN_SCRIPT: &str = "run.bat";
+#[cfg(not(target_os = "windows"))]
+const FORGE_RUN_SCRIPT: &str = "run.sh";
+
+pub fn find_forge_launch(server_path: &str) -> Result<ForgeLaunch, String> {
+    let dir = Path::new(server_path);
+
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .collect();
+    names.sort();
+
+    // New Forge versions with shim
+    if let Some(name) = names.iter().find(|n| n.ends_with("shim.jar")) {
+        return Ok(ForgeLaunch::Jar(name.clone()));
+    }
+
+    if let Some(args_file) = find_forge_args_file(dir) {
+        return Ok(ForgeLaunch::ArgsFile(args_file));
+    }
+
+    // Old Forge versions, forge-<mc>-<forge>.jar or forge-<mc>-<forge>-universal.jar
+    if let Some(name) = names
+        .iter()
+        .find(|n| n.starts_with("forge-") && n.ends_with(".jar") && !n.contains("installer"))
+    {
+        return Ok(ForgeLaunch::Jar(name.clone()));
+    }
+
+    Err("Could not find Forge launch jar or args file".into())
+}
+
+// Prefers the args file the run script points at, in case several Forge versions were installed
+fn find_forge_args_file(dir: &Path) -> Option<String> {
+    if let Ok(script) = fs::read_to_string(dir.join(FORGE_RUN_SCRIPT)) {
+        let referenced = script
+            .split_whitespace()
+            .filter_map(|word| word.trim_matches('"').strip_prefix('@'))
+            .find(|path| path.ends_with(FORGE_ARGS_FILE) && dir.join(path).is_file());
+
+        if let Some(path) = referenced {
+            return Some(path.to_string());
+        }
+    }
+
+    let forge_libs = dir.join("libraries/net/minecraftforge/forge");
+    let mut versions: Vec<String> = fs::read_dir(forge_libs)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().join(FORGE_ARGS_FILE).is_file())
+        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .collect();
+    versions.sort();
+
+    versions.pop().map(|version| {
+        format!(
+            "libraries/net/minecraftforge/forge/{}/{}",
+            version, FORGE_ARGS_FILE
+        )
+    })
+}
+
+/// Splits a Java @argfile into arguments: whitespace separated, quotes group, # comments a line.
+pub fn parse_args_file(content: &str) -> Vec<String> {
+    let mut args = Vec::new();
+
+    for line in content.lines() {
+        let mut current = String::new();
+        let mut in_arg = false;
+        let mut quote: Option<char> = None;
+        let mut chars = line.chars();
+
+        while let Some(c) = chars.next() {
+            match (quote, c) {
+                (None, '#') if !in_arg => break,
+                (None, '"' | '\'') => {
+                    quote = Some(c);
+                    in_arg = true;
+                }
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), '\\') => {
+                    if let Some(next) = chars.next() {
+                        current.push(next);
+                    }
+                }
+                (None, c) if c.is_whitespace() => {
+                    if in_arg {
+                        args.push(std::mem::take(&mut current));
+                        in_arg = false;
+                    }
+                }
+                (_, c) => {
+                    current.push(c);
+                    in_arg = true;
+                }
+            }
+        }
+
+        if in_arg {
+            args.push(current);
+        }
+    }
+
+    args
+}
+
+// JVM arguments the user added to Forge's user_jvm_args.txt
+fn forge_user_jvm_args(server_path: &str) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(Path::new(server_path).join("user_jvm_args.txt")) else {
+        return Vec::new();
+    };
+
+    // Memory is set with Cubely's memory fields, a second -Xmx here would silently win
+    parse_args_file(&content)
+        .into_iter()
+        .filter(|a| !a.starts_with("-Xmx") && !a.starts_with("-Xms"))
+        .collect()
+}
+
+/// Heap flags, the preset's flags, then the custom JVM arguments.
+pub fn jvm_args(server: &ServerConfig, java: JavaVersion) -> Vec<String> {
+    let (min, max) = heap_mb(server);
+
+    let mut args = vec![format!("-Xms{}M", min), format!("-Xmx{}M", max)];
+
+    if let Some(preset) = server.launch.preset {
+        args.extend(resolve_preset(preset, java, max).0);
+    }
+
+    if matches!(server.loader, LoaderType::Forge) {
+        args.extend(forge_user_jvm_args(&server.path));
+    }
+
+    args.extend(server.launch.jvm_args.iter().map(|a| a.trim().to_string()));
+    args
+}
+
+/// Everything after the java binary.
+pub fn launch_args(server: &ServerConfig, java: JavaVersion) -> Result<Vec<String>, String> {
+    let mut args = jvm_args(server, java);
+
+    match server.loader {
+        LoaderType::Vanilla | LoaderType::Fabric => {
+            args.extend(["-jar".to_string(), "server.jar".to_string()]);
+        }
+        LoaderType::Forge => match find_forge_launch(&server.path)? {
+            ForgeLaunch::Jar(jar) => args.extend(["-jar".to_string(), jar]),
+            ForgeLaunch::ArgsFile(path) => args.push(format!("@{}", path)),
+        },
+    }
+
+    args.push("nogui".to_string());
+    args.extend(server.launch.server_args.iter().cloned());
+
+    Ok(args)
+}
+
+// The Java a server runs on, guessed from the version number when Mojang didn't say
+pub fn server_java(server: &ServerConfig) -> JavaVersion {
+    // Servers created before java_major was stored, server.jar lists it since 1.18
+    let major = server.java_major.or_else(|| {
+        server_jar_version_json(&server.path)
+            .and_then(|v| v["java_version"].as_u64())
+            .map(|v| v as u32)
+    });
+
+    match major {
+        Some(major) => JavaVersion::from_major(major),
+        None => JavaVersion::from_mc_version(&server.version),
+    }
+}
+
+#[tauri::command]
+pub fn get_system_memory_mb() -> Option<u64> {
+    total_memory_mb()
+}
+
+#[tauri::command]
+pub fn read_launch_config(server_id: String) -> Result<LaunchConfig, String> {
+    Ok(find_server(&server_id)?.launch)
+}
+
+#[tauri::command]
+pub fn update_launch_config(server_id: String, launch: LaunchConfig) -> Result<(), String> {
+    let mut server = find_server(&server_id)?;
+    server.launch = launch;
+
+    validate_launch(&server)?;
+    save_server_config(&server)
+}
+
+/// The command start_server would run, without starting anything.
+#[tauri::command]
+pub fn preview_launch_command(
+    server_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<LaunchPreview, String> {
+    let server = find_server(&server_id)?;
+    validate_launch(&server)?;
+
+    let java = server_java(&server);
+    let args = launch_args(&server, java)?;
+
+    let java_path = state
+        .java_base_dir
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|base| java.java_binary(base).to_string_lossy().to_string())
+        .unwrap_or("java".into());
+
+    let quote = |arg: &str| {
+        if arg.is_empty() || arg.contains([' ', '"', '\'']) {
+            format!("\"{}\"", arg.replace('"', "\\\""))
+        } else {
+            arg.to_string()
+        }
+    };
+
+    let command_line = std::iter::once(java_path.as_str())
+        .chain(args.iter().map(String::as_str))
+        .map(quote)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Ok(LaunchPreview {
+        java_version: java.major(),
+        dropped_flags: server
+            .launch
+            .preset
+            .map(|p| resolve_preset(p, java, heap_mb(&server).1).1)
+            .unwrap_or_default(),
+        args,
+        command_line,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(launch: LaunchConfig) -> ServerConfig {
+        ServerConfig {
+            id: "test".into(),
+            name: "Test".into(),
+            version: "1.21.1".into(),
+            loader: LoaderType::Vanilla,
+            ram_gb: 1,
+            path: String::new(),
+            created_at: 0,
+            tunnel: None,
+            resource_pack: None,
+            eula: None,
+            launch,
+            java_major: Some(21),
+        }
+    }
+
+    #[test]
+    fn parses_args_files() {
+        let content = "\
+# Forge's comment line
+-Xss4M   -Dname=\"two words\"
+-Dpath='C:\\\\Program Files\\\\x' \"quoted \\\" quote\"
+-Dhash=a#b  # trailing comment
+\"\"
+";
+
+        assert_eq!(
+            parse_args_file(content),
+            vec![
+                "-Xss4M",
+                "-Dname=two words",
+                "-Dpath=C:\\Program Files\\x",
+                "quoted \" quote",
+                "-Dhash=a#b",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_flags_the_java_does_not_have() {
+        let has = |preset, java, flag: &str| {
+            let (kept, dropped) = resolve_preset(preset, java, 4096);
+            assert_ne!(
+                kept.iter().any(|a| a == flag),
+                dropped.iter().any(|a| a == flag)
+            );
+            kept.iter().any(|a| a == flag)
+        };
+
+        let rset = "-XX:G1RSetUpdatingPauseTimePercent=5";
+        assert!(has(JvmPreset::Aikar, JavaVersion::Java8, rset));
+        assert!(has(JvmPreset::Aikar, JavaVersion::Java17, rset));
+        assert!(!has(JvmPreset::Aikar, JavaVersion::Java21, rset));
+        assert!(!has(JvmPreset::Aikar, JavaVersion::Java25, rset));
+
+        let generational = "-XX:+ZGenerational";
+        assert!(!has(JvmPreset::Zgc, JavaVersion::Java8, generational));
+        assert!(!has(JvmPreset::Zgc, JavaVersion::Java17, generational));
+        assert!(has(JvmPreset::Zgc, JavaVersion::Java21, generational));
+        assert!(!has(JvmPreset::Zgc, JavaVersion::Java25, generational));
+
+        assert!(!has(JvmPreset::Zgc, JavaVersion::Java8, "-XX:+UseZGC"));
+        assert!(has(JvmPreset::Zgc, JavaVersion::Java17, "-XX:+UseZGC"));
+    }
+
+    #[test]
+    fn validates_launch_settings() {
+        let ok = server(LaunchConfig::default());
+        assert_eq!(heap_mb(&ok), (1024, 1024));
+        assert!(validate_launch(&ok).is_ok());
+
+        let heap = server(LaunchConfig {
+            min_heap_mb: Some(768),
+            ..Default::default()
+        });
+        assert_eq!(heap_mb(&heap), (768, 1024));
+        assert!(validate_launch(&heap).is_ok());
+
+        let min_over_max = server(LaunchConfig {
+            min_heap_mb: Some(1024),
+            max_heap_mb: Some(768),
+            ..Default::default()
+        });
+        assert!(validate_launch(&min_over_max).is_err());
+
+        let too_small = server(LaunchConfig {
+            max_heap_mb: Some(MIN_HEAP_MB - 1),
+            ..Default::default()
+        });
+        assert!(validate_launch(&too_small).is_err());
+
+        for arg in ["-Xmx4G", " -Xms1G", "-jar", " "] {
+            let jvm = server(LaunchConfig {
+                jvm_args: vec![arg.into()],
+                ..Default::default()
+            });
+            assert!(validate_launch(&jvm).is_err(), "{:?} was accepted", arg);
+        }
+
+        for key in ["", " ", "A=B", "A\0"] {
+            let env = server(LaunchConfig {
+                env: BTreeMap::from([(key.to_string(), "1".to_string())]),
+                ..Default::default()
+            });
+            assert!(validate_launch(&env).is_err(), "{:?} was accepted", key);
+        }
+
+        let java_17 = ServerConfig {
+            java_major: Some(17),
+            ..server(LaunchConfig {
+                preset: Some(JvmPreset::Zgc),
+                ..Default::default()
+            })
+        };
+        assert!(validate_launch(&java_17).is_err());
+    }
+
+    #[test]
+    fn finds_the_args_file_the_run_script_uses() {
+        let dir = std::env::temp_dir().join(format!("cubely-launch-{}", std::process::id()));
+        let forge = dir.join("libraries/net/minecraftforge/forge");
+        let args_file = |version: &str| {
+            format!(
+                "libraries/net/minecraftforge/forge/{}/{}",
+                version, FORGE_ARGS_FILE
+            )
+        };
+
+        for version in ["1.20.1-47.2.0", "1.20.1-47.3.0"] {
+            fs::create_dir_all(forge.join(version)).unwrap();
+            fs::write(dir.join(args_file(version)), "").unwrap();
+        }
+
+        // Without a run script the newest install wins
+        assert_eq!(find_forge_args_file(&dir), Some(args_file("1.20.1-47.3.0")));
+
+        fs::write(
+            dir.join(FORGE_RUN_SCRIPT),
+            format!(
+                "java @user_jvm_args.txt \"@{}\" \"$@\"\n",
+                args_file("1.20.1-47.2.0")
+            ),
+        )
+        .unwrap();
+        assert_eq!(find_forge_args_file(&dir), Some(args_file("1.20.1-47.2.0")));
+
+        // A script pointing at a removed install falls back to the newest
+        fs::remove_dir_all(forge.join("1.20.1-47.2.0")).unwrap();
+        assert_eq!(find_forge_args_file(&dir), Some(args_file("1.20.1-47.3.0")));
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -5,13 +5,20 @@ use playit_api_client::PlayitApi;
 use serde::{Deserialize, Serialize};
 use tauri::Emitter;
 
-use crate::commands::java_manager::JavaVersion;
+use crate::commands::eula::{require_eula, EulaAcceptance};
 use crate::commands::ngrok_manager::{install_ngrok, ngrok_binary, ngrok_installed, start_ngrok};
 use crate::commands::playit_manager::{
     get_playit_public_url, install_playit, playit_binary, playit_installed, start_playit,
 };
+use crate::commands::properties_schema::{
+    applies_to_version, apply_live_properties, issues_error, validate_properties,
+};
+use crate::commands::resource_packs::{start_pack_hosting, stop_pack_hosting, HostedResourcePack};
+use crate::commands::server_icon::read_server_icon;
+use crate::commands::server_launch::{launch_args, server_java, validate_launch, LaunchConfig};
 use crate::{
     commands::server_creation::LoaderType, state::app_state::AppState, utils::path::servers_dir,
+    utils::properties::PropertiesDocument,
 };
 
 #[cfg(target_os = "windows")]
@@ -53,10 +60,41 @@ pub struct ServerConfig {
 
     #[serde(default)]
     pub tunnel: Option<TunnelConfig>,
+
+    #[serde(default)]
+    pub resource_pack: Option<HostedResourcePack>,
+
+    #[serde(default)]
+    pub eula: Option<EulaAcceptance>,
+
+    #[serde(default)]
+    pub launch: LaunchConfig,
+
+    // javaVersion.majorVersion from Mojang's version JSON, None for versions without it
+    #[serde(default)]
+    pub java_major: Option<u32>,
+}
+
+// What the server list shows, the config plus things read from the server folder
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerListing {
+    #[serde(flatten)]
+    pub config: ServerConfig,
+    pub icon: Option<String>, // data URL of server-icon.png
 }
 
 #[tauri::command]
-pub fn list_servers() -> Result<Vec<ServerConfig>, String> {
+pub fn list_servers() -> Result<Vec<ServerListing>, String> {
+    Ok(read_server_configs()?
+        .into_iter()
+        .map(|config| ServerListing {
+            icon: read_server_icon(&config.path),
+            config,
+        })
+        .collect())
+}
+
+pub fn read_server_configs() -> Result<Vec<ServerConfig>, String> {
     let base = servers_dir();
     let mut servers = Vec::new();
 
@@ -87,6 +125,30 @@ pub fn list_servers() -> Result<Vec<ServerConfig>, String> {
     Ok(servers)
 }
 
+// Finds a single server's config by its id
+pub fn find_server(server_id: &str) -> Result<ServerConfig, String> {
+    read_server_configs()?
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or("Server not found".into())
+}
+
+// Reads the cubely.json of a server folder
+pub fn load_server_config(server_path: &str) -> Result<ServerConfig, String> {
+    let raw = fs::read_to_string(PathBuf::from(server_path).join("cubely.json"))
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&raw).map_err(|e| e.to_string())
+}
+
+// Writes a server's cubely.json back to its folder
+pub fn save_server_config(config: &ServerConfig) -> Result<(), String> {
+    fs::write(
+        PathBuf::from(&config.path).join("cubely.json"),
+        serde_json::to_string_pretty(config).unwrap(),
+    )
+    .map_err(|e| e.to_string())
+}
+
 use serde_json::Value;
 use std::collections::HashMap;
 use std::process::{Child, Stdio};
@@ -109,25 +171,38 @@ pub struct ServerProperties {
     pub server_port: u16,
 }
 
+fn properties_path(server_path: &String) -> PathBuf {
+    PathBuf::from(server_path).join("server.properties")
+}
+
 // Returns the HashMap of all the server propertiy pairs
 pub fn map_server_properties(server_path: &String) -> Result<HashMap<String, String>, String> {
-    let content = fs::read_to_string(PathBuf::from(server_path).join("server.properties"))
-        .map_err(|e| e.to_string())?;
+    Ok(PropertiesDocument::load(&properties_path(server_path))?.to_map())
+}
 
-    let mut map = std::collections::HashMap::new();
+// Returns the world folder name from server.properties, "world" when missing
+pub fn level_name(server_path: &String) -> String {
+    map_server_properties(server_path)
+        .ok()
+        .and_then(|map| map.get("level-name").cloned())
+        .unwrap_or("world".into())
+}
 
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
+// version.json bundled inside server.jar since 1.14 (world_version, pack_version...)
+pub fn server_jar_version_json(server_path: &String) -> Option<Value> {
+    use std::io::Read;
 
-        if let Some((k, v)) = line.split_once('=') {
-            map.insert(k.to_string(), v.to_string());
-        }
-    }
+    let file = fs::File::open(PathBuf::from(server_path).join("server.jar")).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
 
-    Ok(map)
+    let mut content = String::new();
+    archive
+        .by_name("version.json")
+        .ok()?
+        .read_to_string(&mut content)
+        .ok()?;
+
+    serde_json::from_str(&content).ok()
 }
 
 #[tauri::command]
@@ -172,41 +247,121 @@ pub fn read_server_properties(server_path: String) -> Result<ServerProperties, S
 pub async fn update_server_properties(
     server_path: String,
     props: ServerProperties,
+    state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
-    let mut map = map_server_properties(&server_path)?;
-
     // Update only keys we control
-    map.insert("level-name".into(), props.level_name);
-    map.insert("motd".into(), props.motd);
-    map.insert("online-mode".into(), props.online_mode.to_string());
-    map.insert("max-players".into(), props.max_players.to_string());
-    map.insert("difficulty".into(), props.difficulty);
-    map.insert("gamemode".into(), props.gamemode);
-    map.insert("force-gamemode".into(), props.force_gamemode.to_string());
-    map.insert("pvp".into(), props.pvp.to_string());
-    map.insert(
-        "spawn-protection".into(),
-        props.spawn_protection.to_string(),
-    );
-    map.insert("view-distance".into(), props.view_distance.to_string());
-    map.insert(
-        "simulation-distance".into(),
-        props.simulation_distance.to_string(),
-    );
-    map.insert("server-port".into(), props.server_port.to_string());
-
-    //  Write back EVERYTHING (including unknown keys)
-    let mut output = String::from("# Generated / Updated by Cubely\n");
-
-    for (k, v) in map.iter() {
-        output.push_str(&format!("{k}={v}\n"));
+    let values = [
+        ("level-name", props.level_name),
+        ("motd", props.motd),
+        ("online-mode", props.online_mode.to_string()),
+        ("max-players", props.max_players.to_string()),
+        ("difficulty", props.difficulty),
+        ("gamemode", props.gamemode),
+        ("force-gamemode", props.force_gamemode.to_string()),
+        ("pvp", props.pvp.to_string()),
+        ("spawn-protection", props.spawn_protection.to_string()),
+        ("view-distance", props.view_distance.to_string()),
+        ("simulation-distance", props.simulation_distance.to_string()),
+        ("server-port", props.server_port.to_string()),
+    ];
+
+    let config = load_server_config(&server_path).ok();
+    let version = config.as_ref().map(|c| c.version.as_str());
+
+    // The form has every field, only write the ones this version reads (pvp moved to a
+    // gamerule in 1.21.9, simulation-distance only exists since 1.18)
+    let values: Vec<(&str, String)> = values
+        .into_iter()
+        .filter(|(key, _)| applies_to_version(key, version))
+        .collect();
+
+    let issues = validate_properties(&values, version);
+    if !issues.is_empty() {
+        return Err(issues_error(&issues));
     }
 
-    fs::write(
-        &PathBuf::from(server_path).join("server.properties"),
-        output,
-    )
-    .map_err(|e| e.to_string())?;
+    set_server_properties(&server_path, &values)?;
+
+    if let Some(config) = config {
+        apply_live_properties(&state, &config.id, &values);
+    }
+
+    Ok(())
+}
+
+// Changes a few keys in place, comments, order and every other line stay untouched
+pub fn set_server_properties(server_path: &String, values: &[(&str, String)]) -> Result<(), String> {
+    let path = properties_path(server_path);
+    let mut doc = PropertiesDocument::load(&path)?;
+
+    for (k, v) in values {
+        doc.set(k, v);
+    }
+
+    doc.save(&path)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PropertyEntry {
+    pub key: String,
+    pub value: String,
+}
+
+/// Every key of server.properties in file order, including ones Cubely has no field for.
+#[tauri::command]
+pub fn read_all_server_properties(server_path: String) -> Result<Vec<PropertyEntry>, String> {
+    Ok(PropertiesDocument::load(&properties_path(&server_path))?
+        .entries()
+        .into_iter()
+        .map(|(key, value)| PropertyEntry { key, value })
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_server_property(server_path: String, key: String) -> Result<Option<String>, String> {
+    Ok(PropertiesDocument::load(&properties_path(&server_path))?
+        .get(&key)
+        .map(String::from))
+}
+
+/// Sets any keys, a `None` value removes the key. New keys are added at the end of the file.
+#[tauri::command]
+pub fn set_server_property_values(
+    server_path: String,
+    values: Vec<(String, Option<String>)>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let path = properties_path(&server_path);
+    let mut doc = PropertiesDocument::load(&path)?;
+
+    if values.iter().any(|(key, _)| key.trim().is_empty()) {
+        return Err("Property keys can't be empty".into());
+    }
+
+    let set: Vec<(&str, String)> = values
+        .iter()
+        .filter_map(|(key, value)| Some((key.as_str(), value.clone()?)))
+        .collect();
+    let config = load_server_config(&server_path).ok();
+    let issues = validate_properties(&set, config.as_ref().map(|c| c.version.as_str()));
+    if !issues.is_empty() {
+        return Err(issues_error(&issues));
+    }
+
+    for (key, value) in &values {
+        match value {
+            Some(value) => doc.set(key, value),
+            None => {
+                doc.remove(key);
+            }
+        }
+    }
+
+    doc.save(&path)?;
+
+    if let Some(config) = config {
+        apply_live_properties(&state, &config.id, &set);
+    }
 
     Ok(())
 }
@@ -258,6 +413,12 @@ pub fn update_server_config(
 
     // Only allow safe fields
     full.name = props.name;
+
+    // A new RAM amount replaces custom heap sizes
+    if full.ram_gb != props.ram_gb {
+        full.launch.min_heap_mb = None;
+        full.launch.max_heap_mb = None;
+    }
     full.ram_gb = props.ram_gb;
     full.tunnel = Some(props.tunnel);
 
@@ -310,8 +471,14 @@ pub async fn start_server(
         }
     } // <- mutex guard DROPPED here
 
+    // The frontend's copy may be older than cubely.json
+    let server = load_server_config(&server.path).unwrap_or(server);
+
+    require_eula(&server)?;
+    validate_launch(&server)?;
+
     // Check and install if required java version is missing
-    let java_version = JavaVersion::from_mc_version(&server.version);
+    let java_version = server_java(&server);
 
     // lock once
     let java_base = {
@@ -348,44 +515,7 @@ pub async fn start_server(
         return Err(format!("Server directory not found: {}", server.path));
     }
 
-    // spawn minecraft
-    let mut cmd = Command::new(java);
-
-    match server.loader {
-        LoaderType::Vanilla | LoaderType::Fabric => {
-            cmd.args([
-                format!("-Xmx{}G", server.ram_gb),
-                format!("-Xms{}G", server.ram_gb),
-                "-jar".into(),
-                "server.jar".into(),
-                "nogui".into(),
-            ]);
-        }
-
-        LoaderType::Forge => {
-            let jar_name = find_forge_entry(&server.path)?;
-
-            cmd.args([
-                    format!("-Xmx{}G", server.ram_gb),
-                    format!("-Xms{}G", server.ram_gb),
-                    "-jar".into(),
-                    jar_name,
-                    "nogui".into(),
-                ]);
-        }
-    };
-
-    cmd.current_dir(&server.path)
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-
-    #[cfg(target_os = "windows")]
-    {
-        cmd.creation_flags(0x08000000); // NO TERMINAL WINDOW
-    }
-
-    let mut mc_child: Child = cmd.spawn().map_err(|e| e.to_string())?;
+    let args = launch_args(&server, java_version)?;
 
     // Logging to frontend
     let app = {
@@ -393,28 +523,6 @@ pub async fn start_server(
         guard.clone().ok_or("App handle not initialized")?
     };
 
-    if let Some(stdout) = mc_child.stdout.take() {
-        let app = app.clone();
-
-        std::thread::spawn(move || {
-            let reader = std::io::BufReader::new(stdout);
-            for line in reader.lines().flatten() {
-                let _ = app.emit("mc-log", line);
-            }
-        });
-    }
-
-    if let Some(stderr) = mc_child.stderr.take() {
-        let app = app.clone();
-
-        std::thread::spawn(move || {
-            let reader = std::io::BufReader::new(stderr);
-            for line in reader.lines().flatten() {
-                let _ = app.emit("mc-log", format!("[ERR] {}", line));
-            }
-        });
-    }
-
     let mut ngrok_child = None;
     let mut playit_child = None;
     let mut public_url = None;
@@ -477,6 +585,63 @@ pub async fn start_server(
         }
     }
 
+    // Players download the pack while joining, so it is served as long as the server runs.
+    // Set up before the server reads server.properties, the URL may have changed
+    if let Some(pack) = &server.resource_pack {
+        if let Err(e) = start_pack_hosting(&state, &server, pack, ngrok_child.is_some()).await {
+            let _ = app.emit("mc-log", format!("[CUBELY] Failed to host the resource pack: {}", e));
+        }
+    }
+
+    // spawn minecraft
+    let mut cmd = Command::new(java);
+
+    cmd.args(args).envs(&server.launch.env);
+
+    cmd.current_dir(&server.path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(0x08000000); // NO TERMINAL WINDOW
+    }
+
+    let mut mc_child: Child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            // The tunnels and the pack were started for this server
+            for child in [ngrok_child, playit_child].iter_mut().flatten() {
+                child.kill().ok();
+            }
+            stop_pack_hosting(&state, &server.id);
+            return Err(e.to_string());
+        }
+    };
+
+    if let Some(stdout) = mc_child.stdout.take() {
+        let app = app.clone();
+
+        std::thread::spawn(move || {
+            let reader = std::io::BufReader::new(stdout);
+            for line in reader.lines().flatten() {
+                let _ = app.emit("mc-log", line);
+            }
+        });
+    }
+
+    if let Some(stderr) = mc_child.stderr.take() {
+        let app = app.clone();
+
+        std::thread::spawn(move || {
+            let reader = std::io::BufReader::new(stderr);
+            for line in reader.lines().flatten() {
+                let _ = app.emit("mc-log", format!("[ERR] {}", line));
+            }
+        });
+    }
+
     let info = ActiveServerInfo {
         server_name: server.name.clone(),
         server_id: server.id.clone(),
@@ -496,31 +661,19 @@ pub async fn start_server(
     Ok(info)
 }
 
-fn find_forge_entry(server_path: &str) -> Result<String, String> {
-    let dir = PathBuf::from(server_path);
-
-    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-
-        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            // New Forge versions with shim
-            if name.contains("shim.jar") {
-                return Ok(name.to_string());
-            }
-
-            // Old Forge versions with universal jar
-            if name.contains("forge-") && name.contains("universal.jar") {
-                return Ok(name.to_string());
-            }
-        }
-    }
+#[tauri::command]
+pub fn stop_server(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    stop_active_server(&state)
+}
 
-    Err("Could not find Forge launch jar".into())
+// Returns true if the server with this id is the one currently running
+pub fn is_server_running(state: &AppState, server_id: &str) -> bool {
+    let active = state.active_server.lock().unwrap();
+    active.as_ref().is_some_and(|s| s.server_id == server_id)
 }
 
-#[tauri::command]
-pub fn stop_server(state: tauri::State<'_, AppState>) -> Result<(), String> {
+// Shared by stop_server and anything that needs the world files released (backups, restores...)
+pub fn stop_active_server(state: &AppState) -> Result<(), String> {
     let mut active = state.active_server.lock().unwrap();
 
     if let Some(mut server) = active.take() {
@@ -540,6 +693,8 @@ pub fn stop_server(state: tauri::State<'_, AppState>) -> Result<(), String> {
             ngrok.kill().ok();
         }
 
+        stop_pack_hosting(state, &server.server_id);
+
         Ok(())
     } else {
         Err("No active server".into())
@@ -559,11 +714,7 @@ pub fn delete_server(server_id: String, state: tauri::State<'_, AppState>) -> Re
     }
 
     // Find server config
-    let servers = list_servers()?;
-    let server = servers
-        .into_iter()
-        .find(|s| s.id == server_id)
-        .ok_or("Server not found")?;
+    let server = find_server(&server_id)?;
 
     let server_path = PathBuf::from(&server.path);
 
@@ -0,0 +1,337 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::server_management::{find_server, level_name};
+use crate::nbt::{
+    region::{self, ChunkLocation, REGION_CHUNKS, SECTOR_SIZE},
+    Tag,
+};
+use crate::utils::{archive::dir_size, path::resolve_server_file};
+
+/// FINDING THE DIMENSIONS OF A WORLD
+
+pub struct Dimension {
+    pub id: String, // "minecraft:overworld"
+    // Folder holding region/, entities/ and poi/
+    pub dir: PathBuf,
+}
+
+// Vanilla keeps the nether and end inside the world folder, Bukkit based servers
+// use separate `<level>_nether` / `<level>_the_end` folders
+fn vanilla_dimension(server_path: &Path, level: &str, suffix: &str, dim: &str) -> PathBuf {
+    let split = server_path.join(format!("{}_{}", level, suffix)).join(dim);
+
+    if split.join("region").is_dir() {
+        split
+    } else {
+        server_path.join(level).join(dim)
+    }
+}
+
+fn find_custom_dimensions(dir: &Path, id: String, found: &mut Vec<Dimension>) {
+    if dir.join("region").is_dir() {
+        found.push(Dimension {
+            id: id.clone(),
+            dir: dir.to_path_buf(),
+        });
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.path().is_dir() && !["region", "entities", "poi", "data"].contains(&name.as_str())
+        {
+            find_custom_dimensions(&entry.path(), format!("{}/{}", id, name), found);
+        }
+    }
+}
+
+/// Every dimension of a world that has region files.
+pub fn world_dimensions(server_path: &Path, level: &str) -> Vec<Dimension> {
+    let mut dimensions = vec![
+        Dimension {
+            id: "minecraft:overworld".into(),
+            dir: server_path.join(level),
+        },
+        Dimension {
+            id: "minecraft:the_nether".into(),
+            dir: vanilla_dimension(server_path, level, "nether", "DIM-1"),
+        },
+        Dimension {
+            id: "minecraft:the_end".into(),
+            dir: vanilla_dimension(server_path, level, "the_end", "DIM1"),
+        },
+    ];
+
+    // Datapack dimensions: dimensions/<namespace>/<path>/region
+    if let Ok(namespaces) = fs::read_dir(server_path.join(level).join("dimensions")) {
+        for namespace in namespaces.flatten() {
+            let id = format!("{}:", namespace.file_name().to_string_lossy());
+            let mut found = Vec::new();
+
+            if let Ok(children) = fs::read_dir(namespace.path()) {
+                for child in children.flatten().filter(|c| c.path().is_dir()) {
+                    find_custom_dimensions(
+                        &child.path(),
+                        format!("{}{}", id, child.file_name().to_string_lossy()),
+                        &mut found,
+                    );
+                }
+            }
+
+            dimensions.extend(found);
+        }
+    }
+
+    dimensions.retain(|d| d.dir.join("region").is_dir());
+    dimensions
+}
+
+/// WORLD SIZE REPORT
+
+const HEATMAP_MAX_CELLS: i32 = 64;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChunkInfo {
+    pub x: i32,
+    pub z: i32,
+    pub size_bytes: u64,
+    pub inhabited_ticks: Option<i64>,
+    pub last_update: Option<i64>,
+    pub saved_at: u32, // unix seconds, from the region header
+}
+
+/// Chunk counts and summed InhabitedTime on a coarse grid, rows of `width` cells.
+/// Cell (0, 0) starts at chunk (`min_x`, `min_z`) and each cell covers `cell_size` x `cell_size` chunks.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Heatmap {
+    pub min_x: i32,
+    pub min_z: i32,
+    pub cell_size: i32,
+    pub width: i32,
+    pub height: i32,
+    pub chunk_counts: Vec<u32>,
+    pub inhabited_ticks: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DimensionReport {
+    pub id: String,
+    pub path: String, // relative to the server folder
+    pub region_files: usize,
+    pub chunk_count: usize,
+    pub size_bytes: u64, // region/, entities/ and poi/
+    pub total_inhabited_ticks: i64,
+    pub max_inhabited_ticks: i64,
+    pub unreadable_chunks: usize,
+    pub heatmap: Heatmap,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorldReport {
+    pub world: String,
+    pub size_bytes: u64,
+    pub dimensions: Vec<DimensionReport>,
+}
+
+// 1.18+ stores these at the root of the chunk, older versions inside "Level"
+fn chunk_value(chunk: &Tag, key: &str) -> Option<i64> {
+    chunk
+        .get(key)
+        .or_else(|| chunk.get_path(&format!("Level/{}", key)))
+        .and_then(Tag::as_i64)
+}
+
+fn chunk_info(
+    file: &mut fs::File,
+    path: &Path,
+    region: (i32, i32),
+    location: &ChunkLocation,
+) -> ChunkInfo {
+    let chunk = region::read_chunk(file, path, region, location).ok();
+
+    ChunkInfo {
+        x: region.0 * REGION_CHUNKS + location.local_x,
+        z: region.1 * REGION_CHUNKS + location.local_z,
+        size_bytes: location.sector_count as u64 * SECTOR_SIZE,
+        inhabited_ticks: chunk.as_ref().and_then(|c| chunk_value(c, "InhabitedTime")),
+        last_update: chunk.as_ref().and_then(|c| chunk_value(c, "LastUpdate")),
+        saved_at: location.timestamp,
+    }
+}
+
+/// Reads every chunk of a dimension.
+pub fn read_dimension_chunks(dimension_dir: &Path) -> (usize, Vec<ChunkInfo>) {
+    let regions = region::list_region_files(&dimension_dir.join("region"));
+    let mut chunks = Vec::new();
+
+    for (x, z, path) in &regions {
+        let Ok(mut file) = fs::File::open(path) else {
+            continue;
+        };
+        let Ok(locations) = region::read_header(&mut file) else {
+            continue;
+        };
+
+        for location in &locations {
+            chunks.push(chunk_info(&mut file, path, (*x, *z), location));
+        }
+    }
+
+    (regions.len(), chunks)
+}
+
+fn build_heatmap(chunks: &[ChunkInfo]) -> Heatmap {
+    if chunks.is_empty() {
+        return Heatmap::default();
+    }
+
+    let min_x = chunks.iter().map(|c| c.x).min().unwrap_or(0);
+    let max_x = chunks.iter().map(|c| c.x).max().unwrap_or(0);
+    let min_z = chunks.iter().map(|c| c.z).min().unwrap_or(0);
+    let max_z = chunks.iter().map(|c| c.z).max().unwrap_or(0);
+
+    let span = (max_x - min_x + 1).max(max_z - min_z + 1);
+    let mut cell_size = 1;
+    while span > cell_size * HEATMAP_MAX_CELLS {
+        cell_size *= 2;
+    }
+
+    let width = (max_x - min_x) / cell_size + 1;
+    let height = (max_z - min_z) / cell_size + 1;
+
+    let mut heatmap = Heatmap {
+        min_x,
+        min_z,
+        cell_size,
+        width,
+        height,
+        chunk_counts: vec![0; (width * height) as usize],
+        inhabited_ticks: vec![0; (width * height) as usize],
+    };
+
+    for chunk in chunks {
+        let cell = ((chunk.z - min_z) / cell_size * width + (chunk.x - min_x) / cell_size) as usize;
+        heatmap.chunk_counts[cell] += 1;
+        heatmap.inhabited_ticks[cell] += chunk.inhabited_ticks.unwrap_or(0);
+    }
+
+    heatmap
+}
+
+fn relative(server_path: &Path, dir: &Path) -> String {
+    dir.strip_prefix(server_path)
+        .unwrap_or(dir)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[tauri::command]
+pub async fn analyze_world(
+    server_id: String,
+    world: Option<String>,
+) -> Result<WorldReport, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+    let server_path = PathBuf::from(&server.path);
+
+    if !resolve_server_file(&server.path, &world)?
+        .join("level.dat")
+        .exists()
+    {
+        return Err(format!("{} is not a world", world));
+    }
+
+    let world_dir = world.clone();
+
+    // Every chunk of every region gets decompressed, far too slow for the async runtime
+    let dimensions =
+        tokio::task::spawn_blocking(move || analyze_dimensions(&server_path, &world_dir))
+            .await
+            .map_err(|e| e.to_string())?;
+
+    Ok(WorldReport {
+        world,
+        size_bytes: dimensions.iter().map(|d| d.size_bytes).sum(),
+        dimensions,
+    })
+}
+
+fn analyze_dimensions(server_path: &Path, world: &str) -> Vec<DimensionReport> {
+    let mut dimensions = Vec::new();
+
+    for dimension in world_dimensions(server_path, world) {
+        let (region_files, chunks) = read_dimension_chunks(&dimension.dir);
+
+        let size_bytes = ["region", "entities", "poi"]
+            .iter()
+            .map(|d| dir_size(&dimension.dir.join(d)))
+            .sum();
+
+        dimensions.push(DimensionReport {
+            id: dimension.id,
+            path: relative(server_path, &dimension.dir),
+            region_files,
+            chunk_count: chunks.len(),
+            size_bytes,
+            total_inhabited_ticks: chunks.iter().filter_map(|c| c.inhabited_ticks).sum(),
+            max_inhabited_ticks: chunks
+                .iter()
+                .filter_map(|c| c.inhabited_ticks)
+                .max()
+                .unwrap_or(0),
+            unreadable_chunks: chunks
+                .iter()
+                .filter(|c| c.inhabited_ticks.is_none())
+                .count(),
+            heatmap: build_heatmap(&chunks),
+        });
+    }
+
+    dimensions
+}
+
+/// Per-chunk details of one region file, for drilling into a heatmap cell.
+#[tauri::command]
+pub async fn read_region_chunks(
+    server_id: String,
+    world: Option<String>,
+    dimension: String,
+    region_x: i32,
+    region_z: i32,
+) -> Result<Vec<ChunkInfo>, String> {
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+
+    let dimension = world_dimensions(Path::new(&server.path), &world)
+        .into_iter()
+        .find(|d| d.id == dimension)
+        .ok_or(format!("{} has no dimension {}", world, dimension))?;
+
+    let path = dimension
+        .dir
+        .join("region")
+        .join(format!("r.{}.{}.mca", region_x, region_z));
+
+    tokio::task::spawn_blocking(move || {
+        let Ok(mut file) = fs::File::open(&path) else {
+            return Ok(Vec::new());
+        };
+
+        let locations = region::read_header(&mut file)?;
+
+        Ok(locations
+            .iter()
+            .map(|location| chunk_info(&mut file, &path, (region_x, region_z), location))
+            .collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
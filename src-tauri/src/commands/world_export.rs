@@ -0,0 +1,101 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use zip::ZipWriter;
+
+use crate::commands::backup_manager::with_world_flushed;
+use crate::commands::server_management::{find_server, level_name};
+use crate::state::app_state::AppState;
+use crate::utils::{archive::add_dir_to_zip, path::resolve_server_file};
+
+/// EXPORTING A WORLD FOR SINGLEPLAYER
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorldExport {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+// Files that only mean something to the server that wrote them
+const SERVER_ONLY_FILES: [&str; 2] = ["session.lock", "uid.dat"];
+
+fn write_world_zip(server_path: &Path, world: &str, dest: &Path) -> Result<(), String> {
+    let file = fs::File::create(dest).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+
+    // Bukkit keeps the nether and end in <world>_nether/DIM-1 and <world>_the_end/DIM1,
+    // singleplayer expects them inside the world folder
+    let split: Vec<(PathBuf, &str)> = [("nether", "DIM-1"), ("the_end", "DIM1")]
+        .into_iter()
+        .map(|(suffix, dim)| {
+            (
+                server_path.join(format!("{}_{}", world, suffix)).join(dim),
+                dim,
+            )
+        })
+        .filter(|(dir, _)| dir.is_dir())
+        .collect();
+
+    add_dir_to_zip(&mut zip, &server_path.join(world), world, &|rel| {
+        SERVER_ONLY_FILES.contains(&rel) || split.iter().any(|(_, dim)| rel == *dim)
+    })?;
+
+    for (dir, dim) in &split {
+        add_dir_to_zip(&mut zip, dir, &format!("{}/{}", world, dim), &|_| false)?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Zips a world so it can be dropped into `.minecraft/saves`. [Ex: world.zip -> world/level.dat]
+///
+/// `dest` is the zip to write, or a folder to write `<world>.zip` into. A running
+/// server is flushed to disk and paused from autosaving while the zip is written.
+#[tauri::command]
+pub async fn export_world(
+    server_id: String,
+    world: Option<String>,
+    dest: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<WorldExport, String> {
+    let server = find_server(&server_id)?;
+    let server_path = PathBuf::from(&server.path);
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+
+    if !resolve_server_file(&server.path, &world)?
+        .join("level.dat")
+        .exists()
+    {
+        return Err(format!("{} is not a world of this server", world));
+    }
+
+    let mut dest = PathBuf::from(dest);
+    if dest.is_dir() {
+        dest = dest.join(format!("{}.zip", world));
+    }
+
+    let partial = dest.with_extension("zip.part");
+    let target = dest.clone();
+
+    with_world_flushed(&state, &server.id, move || {
+        let result = write_world_zip(&server_path, &world, &partial)
+            .and_then(|_| fs::rename(&partial, &target).map_err(|e| e.to_string()));
+
+        if result.is_err() {
+            fs::remove_file(&partial).ok();
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("Failed to export world: {}", e))?;
+
+    Ok(WorldExport {
+        path: dest.to_string_lossy().to_string(),
+        size_bytes: fs::metadata(&dest).map(|m| m.len()).unwrap_or(0),
+    })
+}
@@ -0,0 +1,498 @@
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::commands::server_management::{
+    find_server, level_name, server_jar_version_json, set_server_properties, ServerConfig,
+};
+use crate::commands::world_manager::{require_free, require_stopped, world_info, WorldInfo};
+use crate::nbt::{self, Tag};
+use crate::state::app_state::AppState;
+use crate::utils::{
+    archive::{dir_size, extract_zip_filtered},
+    path::minecraft_saves_dir,
+};
+
+/// IMPORTING SINGLEPLAYER WORLDS
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportableWorld {
+    pub source: String, // folder or zip path
+    pub folder_name: String,
+    pub level_name: Option<String>,
+    pub data_version: Option<i32>,
+    pub version_name: Option<String>,
+    pub last_played: Option<i64>, // unix millis
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportCheck {
+    pub world: ImportableWorld,
+    pub server_data_version: Option<i32>,
+    // The world was saved by a newer Minecraft than the server runs
+    pub downgrade: bool,
+    pub warnings: Vec<String>,
+}
+
+// Where a world lives inside its source
+enum WorldSource {
+    // `root` is the world folder; split nether / end folders may sit next to it
+    Folder { root: PathBuf },
+    // `prefix` is the folder holding level.dat inside the zip ("" for the zip root)
+    Zip { path: PathBuf, prefix: String },
+}
+
+fn locate(source: &Path) -> Result<WorldSource, String> {
+    if source.is_dir() {
+        if !source.join("level.dat").exists() {
+            return Err(format!("{} has no level.dat", source.display()));
+        }
+        return Ok(WorldSource::Folder {
+            root: source.to_path_buf(),
+        });
+    }
+
+    let file = fs::File::open(source).map_err(|e| e.to_string())?;
+    let archive = ZipArchive::new(file).map_err(|_| "Not a folder or zip file".to_string())?;
+
+    let prefix = world_prefix(archive.file_names())
+        .ok_or("The zip doesn't contain a world (no level.dat)")?;
+
+    Ok(WorldSource::Zip {
+        path: source.to_path_buf(),
+        prefix,
+    })
+}
+
+// The folder of the shallowest level.dat is the world, deeper ones could be Bukkit's
+// split dimensions. A zip of a whole server has those next to the world, at the same depth.
+fn world_prefix<'a>(names: impl Iterator<Item = &'a str>) -> Option<String> {
+    names
+        .filter(|n| *n == "level.dat" || n.ends_with("/level.dat"))
+        .map(|n| n.trim_end_matches("level.dat").trim_end_matches('/'))
+        .min_by_key(|prefix| {
+            let dimension = prefix.ends_with("_nether") || prefix.ends_with("_the_end");
+            (prefix.matches('/').count(), dimension)
+        })
+        .map(String::from)
+}
+
+fn read_level_dat(source: &WorldSource) -> Result<Tag, String> {
+    let file = match source {
+        WorldSource::Folder { root } => nbt::read_file(&root.join("level.dat"))?,
+        WorldSource::Zip { path, prefix } => {
+            let mut archive = ZipArchive::new(fs::File::open(path).map_err(|e| e.to_string())?)
+                .map_err(|e| e.to_string())?;
+
+            let name = if prefix.is_empty() {
+                "level.dat".to_string()
+            } else {
+                format!("{}/level.dat", prefix)
+            };
+
+            let mut bytes = Vec::new();
+            archive
+                .by_name(&name)
+                .map_err(|e| e.to_string())?
+                .read_to_end(&mut bytes)
+                .map_err(|e| e.to_string())?;
+
+            nbt::from_bytes(&bytes)?
+        }
+    };
+
+    file.root
+        .get("Data")
+        .cloned()
+        .ok_or("level.dat has no Data compound".into())
+}
+
+fn describe(source_path: &Path, source: &WorldSource) -> Result<ImportableWorld, String> {
+    let data = read_level_dat(source)?;
+    let int = |key: &str| data.get(key).and_then(Tag::as_i64);
+
+    let (folder_name, size_bytes) = match source {
+        WorldSource::Folder { root } => (
+            root.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            dir_size(root),
+        ),
+        WorldSource::Zip { path, prefix } => (
+            prefix
+                .rsplit('/')
+                .next()
+                .filter(|p| !p.is_empty())
+                .map(String::from)
+                .unwrap_or_else(|| {
+                    path.file_stem()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string()
+                }),
+            fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        ),
+    };
+
+    Ok(ImportableWorld {
+        source: source_path.to_string_lossy().to_string(),
+        folder_name,
+        level_name: data
+            .get("LevelName")
+            .and_then(Tag::as_str)
+            .map(String::from),
+        data_version: int("DataVersion").map(|v| v as i32),
+        version_name: data
+            .get_path("Version/Name")
+            .and_then(Tag::as_str)
+            .map(String::from),
+        last_played: int("LastPlayed"),
+        size_bytes,
+    })
+}
+
+/// The data version the server's Minecraft writes, from version.json inside server.jar
+/// (1.14+), falling back to the active world's level.dat.
+pub fn server_data_version(server_path: &Path) -> Option<i32> {
+    let server = server_path.to_string_lossy().to_string();
+    let from_jar = server_jar_version_json(&server).and_then(|json| json["world_version"].as_i64());
+
+    from_jar
+        .or_else(|| {
+            let level = level_name(&server);
+            nbt::read_file(&server_path.join(level).join("level.dat"))
+                .ok()?
+                .root
+                .get_path("Data/DataVersion")?
+                .as_i64()
+        })
+        .map(|v| v as i32)
+}
+
+// Bukkit based servers keep the nether and end in separate <level>_nether / <level>_the_end folders
+fn uses_split_layout(server_path: &Path) -> bool {
+    server_path.join("bukkit.yml").exists()
+}
+
+/// Maps a path relative to the folder holding the world (so starting with the world's
+/// own folder name) to its place in a vanilla layout world, or `None` to skip it.
+fn logical_path(rel: &str, root: &str) -> Option<String> {
+    let logical = if root.is_empty() {
+        rel.to_string()
+    } else if let Some(rest) = rel.strip_prefix(&format!("{}/", root)) {
+        rest.to_string()
+    } else if let Some(rest) = rel.strip_prefix(&format!("{}_nether/", root)) {
+        rest.starts_with("DIM-1/").then(|| rest.to_string())?
+    } else if let Some(rest) = rel.strip_prefix(&format!("{}_the_end/", root)) {
+        rest.starts_with("DIM1/").then(|| rest.to_string())?
+    } else {
+        return None;
+    };
+
+    // session.lock belongs to whoever had the world open, uid.dat must be unique per Bukkit world
+    if logical.is_empty() || logical == "session.lock" || logical == "uid.dat" {
+        return None;
+    }
+
+    Some(logical)
+}
+
+fn target_path(logical: &str, name: &str, split: bool) -> PathBuf {
+    if split {
+        if logical == "DIM-1" || logical.starts_with("DIM-1/") {
+            return PathBuf::from(format!("{}_nether", name)).join(logical);
+        }
+        if logical == "DIM1" || logical.starts_with("DIM1/") {
+            return PathBuf::from(format!("{}_the_end", name)).join(logical);
+        }
+    }
+
+    PathBuf::from(name).join(logical)
+}
+
+fn copy_folder_world(
+    root: &Path,
+    server_path: &Path,
+    name: &str,
+    split: bool,
+) -> Result<(), String> {
+    let parent = root.parent().ok_or("Invalid world folder")?;
+    let root_name = root
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    // Only a server folder has split dimensions next to the world, in a singleplayer
+    // saves folder <name>_nether is just another world
+    let source_split = uses_split_layout(parent) || parent.join("server.properties").exists();
+
+    let mut dirs = vec![root_name.clone()];
+    if source_split {
+        dirs.push(format!("{}_nether", root_name));
+        dirs.push(format!("{}_the_end", root_name));
+    }
+
+    let mut stack: Vec<PathBuf> = dirs
+        .iter()
+        .map(|d| parent.join(d))
+        .filter(|d| d.is_dir())
+        .collect();
+
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir).map_err(|e| e.to_string())?.flatten() {
+            let path = entry.path();
+
+            if path.is_dir() {
+                stack.push(path);
+                continue;
+            }
+
+            let rel = path
+                .strip_prefix(parent)
+                .map_err(|e| e.to_string())?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            let Some(logical) = logical_path(&rel, &root_name) else {
+                continue;
+            };
+
+            let target = server_path.join(target_path(&logical, name, split));
+            if let Some(p) = target.parent() {
+                fs::create_dir_all(p).map_err(|e| e.to_string())?;
+            }
+
+            fs::copy(&path, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn copy_zip_world(
+    zip: &Path,
+    prefix: &str,
+    server_path: &Path,
+    name: &str,
+    split: bool,
+) -> Result<(), String> {
+    let file = fs::File::open(zip).map_err(|e| e.to_string())?;
+
+    extract_zip_filtered(file, server_path, &|rel| {
+        let rel = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        logical_path(&rel, prefix).map(|logical| target_path(&logical, name, split))
+    })?;
+
+    Ok(())
+}
+
+fn check(server_path: &Path, source: &Path) -> Result<(ImportCheck, WorldSource), String> {
+    let located = locate(source)?;
+    let world = describe(source, &located)?;
+    let server_data_version = server_data_version(server_path);
+
+    let mut warnings = Vec::new();
+
+    let downgrade = match (world.data_version, server_data_version) {
+        (Some(world_version), Some(server_version)) => world_version > server_version,
+        _ => false,
+    };
+
+    if downgrade {
+        warnings.push(format!(
+            "This world was last played on {}, which is newer than the server. Minecraft can't load it or may corrupt it.",
+            world.version_name.as_deref().unwrap_or("a newer version")
+        ));
+    }
+
+    if world.data_version.is_none() || server_data_version.is_none() {
+        warnings.push("Couldn't compare the world's Minecraft version with the server's".into());
+    }
+
+    if let Some((world_version, server_version)) = world.data_version.zip(server_data_version) {
+        if world_version < server_version {
+            warnings.push(
+                "The world is from an older version and will be upgraded when the server loads it. Keep a copy if you want to play it in singleplayer again.".into(),
+            );
+        }
+    }
+
+    Ok((
+        ImportCheck {
+            world,
+            server_data_version,
+            downgrade,
+            warnings,
+        },
+        located,
+    ))
+}
+
+/// Worlds in the Minecraft launcher's saves folder.
+#[tauri::command]
+pub async fn list_singleplayer_worlds() -> Result<Vec<ImportableWorld>, String> {
+    let Some(saves) = minecraft_saves_dir() else {
+        return Ok(Vec::new());
+    };
+
+    // Sizing every world walks all of their files
+    tokio::task::spawn_blocking(move || {
+        let Ok(entries) = fs::read_dir(&saves) else {
+            return Ok(Vec::new());
+        };
+
+        let mut worlds: Vec<ImportableWorld> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.join("level.dat").exists())
+            .filter_map(|p| describe(&p, &WorldSource::Folder { root: p.clone() }).ok())
+            .collect();
+
+        worlds.sort_by_key(|w| std::cmp::Reverse(w.last_played));
+
+        Ok(worlds)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Describes a world folder or zip and compares its version with the server's.
+#[tauri::command]
+pub async fn check_world_import(server_id: String, source: String) -> Result<ImportCheck, String> {
+    let server = find_server(&server_id)?;
+
+    tokio::task::spawn_blocking(move || {
+        check(Path::new(&server.path), Path::new(&source)).map(|(check, _)| check)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn import(
+    server: &ServerConfig,
+    source: &Path,
+    name: &str,
+    set_active: bool,
+    allow_downgrade: bool,
+) -> Result<WorldInfo, String> {
+    let server_path = Path::new(&server.path);
+    require_free(server_path, name)?;
+
+    let (check, located) = check(server_path, source)?;
+
+    if check.downgrade && !allow_downgrade {
+        return Err(check.warnings.join("\n"));
+    }
+
+    let split = uses_split_layout(server_path);
+
+    let result = match &located {
+        WorldSource::Folder { root } => copy_folder_world(root, server_path, name, split),
+        WorldSource::Zip { path, prefix } => copy_zip_world(path, prefix, server_path, name, split),
+    };
+
+    if let Err(e) = result {
+        for dir in [
+            name.to_string(),
+            format!("{}_nether", name),
+            format!("{}_the_end", name),
+        ] {
+            fs::remove_dir_all(server_path.join(dir)).ok();
+        }
+        return Err(format!("Failed to import world: {}", e));
+    }
+
+    if set_active {
+        set_server_properties(&server.path, &[("level-name", name.to_string())])?;
+    }
+
+    Ok(world_info(server_path, name, set_active))
+}
+
+/// Copies a world folder or zip into the server as `name`, in the layout the server expects.
+///
+/// Refuses worlds from a newer Minecraft than the server unless `allow_downgrade` is set.
+#[tauri::command]
+pub async fn import_world(
+    server_id: String,
+    source: String,
+    name: String,
+    set_active: bool,
+    allow_downgrade: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<WorldInfo, String> {
+    if set_active {
+        require_stopped(&state, &server_id)?;
+    }
+
+    let server = find_server(&server_id)?;
+    let name = name.trim().to_string();
+
+    // Copying and sizing a world can take minutes
+    tokio::task::spawn_blocking(move || {
+        import(
+            &server,
+            Path::new(&source),
+            &name,
+            set_active,
+            allow_downgrade,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_world_in_a_zip() {
+        let prefix = |names: &[&str]| world_prefix(names.iter().copied());
+
+        assert_eq!(prefix(&["level.dat", "region/r.0.0.mca"]), Some("".into()));
+        assert_eq!(
+            prefix(&["My World/level.dat", "My World/DIM-1/region/r.0.0.mca"]),
+            Some("My World".into())
+        );
+        assert_eq!(prefix(&["world/region/r.0.0.mca"]), None);
+
+        // A zipped Bukkit server, whatever order the folders were added in
+        for names in [
+            [
+                "server/world_nether/level.dat",
+                "server/world/level.dat",
+                "server/world_the_end/level.dat",
+            ],
+            [
+                "server/world_the_end/level.dat",
+                "server/world_nether/level.dat",
+                "server/world/level.dat",
+            ],
+        ] {
+            assert_eq!(prefix(&names), Some("server/world".into()));
+        }
+
+        // On its own a world can still be called that
+        assert_eq!(
+            prefix(&["hell_nether/level.dat", "hell_nether/region/r.0.0.mca"]),
+            Some("hell_nether".into())
+        );
+    }
+}
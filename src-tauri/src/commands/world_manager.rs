@@ -0,0 +1,340 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::backup_manager::world_dir_names;
+use crate::commands::server_management::{
+    find_server, is_server_running, level_name, set_server_properties,
+};
+use crate::nbt::{self, Tag};
+use crate::state::app_state::AppState;
+use crate::utils::archive::{copy_dir, dir_size};
+
+/// LISTING AND MANAGING THE WORLDS OF A SERVER
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorldInfo {
+    pub name: String, // folder name, what level-name points at
+    pub level_name: Option<String>,
+    pub active: bool,
+    // The active world hasn't been generated yet, the server creates it on the next start
+    pub pending: bool,
+    pub size_bytes: u64, // including <name>_nether / <name>_the_end on Bukkit servers
+    pub last_played: Option<i64>, // unix millis
+    pub data_version: Option<i32>,
+    pub version_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct NewWorldOptions {
+    pub seed: Option<String>,
+    pub level_type: Option<String>, // "minecraft:normal", "minecraft:flat"...
+    pub generator_settings: Option<String>, // JSON, used by flat worlds
+}
+
+pub fn validate_world_name(name: &str) -> Result<(), String> {
+    let name = name.trim();
+
+    if name.is_empty() || name.len() > 64 {
+        return Err("World name must be between 1 and 64 characters".into());
+    }
+
+    if name == "." || name == ".." || name.starts_with('.') {
+        return Err("World name can't start with a dot".into());
+    }
+
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || " _-()".contains(c))
+    {
+        return Err("World name can only contain letters, numbers, spaces, _ - ( and )".into());
+    }
+
+    Ok(())
+}
+
+pub fn require_stopped(state: &AppState, server_id: &str) -> Result<(), String> {
+    if is_server_running(state, server_id) {
+        return Err("Stop the server before changing its worlds".into());
+    }
+    Ok(())
+}
+
+// Worlds copied in by hand (world.old, Bob's World) don't follow the rules for new names,
+// an existing one only has to stay a folder directly inside the server
+fn require_world(server_path: &Path, name: &str) -> Result<(), String> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(format!("{} is not a valid world folder", name));
+    }
+
+    if !server_path.join(name).join("level.dat").exists() {
+        return Err(format!("{} is not a world of this server", name));
+    }
+
+    Ok(())
+}
+
+pub fn require_free(server_path: &Path, name: &str) -> Result<(), String> {
+    validate_world_name(name)?;
+
+    if !world_dir_names(server_path, name).is_empty() {
+        return Err(format!("A folder named {} already exists", name));
+    }
+
+    Ok(())
+}
+
+// Keeps the name shown in-game in sync with the folder, not fatal if it fails
+fn sync_level_name(server_path: &Path, name: &str) {
+    let level_dat = server_path.join(name).join("level.dat");
+
+    if let Ok(mut file) = nbt::read_file(&level_dat) {
+        if let Some(data) = file.root.get_mut("Data") {
+            data.insert("LevelName", Tag::String(name.to_string()));
+            nbt::write_file(&level_dat, &file).ok();
+        }
+    }
+}
+
+pub fn world_info(server_path: &Path, name: &str, active: bool) -> WorldInfo {
+    let data = nbt::read_file(&server_path.join(name).join("level.dat"))
+        .ok()
+        .and_then(|file| file.root.get("Data").cloned());
+
+    let int = |key: &str| data.as_ref().and_then(|d| d.get(key)).and_then(Tag::as_i64);
+    let text = |path: &str| {
+        data.as_ref()
+            .and_then(|d| d.get_path(path))
+            .and_then(Tag::as_str)
+            .map(String::from)
+    };
+
+    WorldInfo {
+        name: name.to_string(),
+        level_name: text("LevelName"),
+        active,
+        pending: data.is_none(),
+        size_bytes: world_dir_names(server_path, name)
+            .iter()
+            .map(|dir| dir_size(&server_path.join(dir)))
+            .sum(),
+        last_played: int("LastPlayed"),
+        data_version: int("DataVersion").map(|v| v as i32),
+        version_name: text("Version/Name"),
+    }
+}
+
+#[tauri::command]
+pub async fn list_worlds(server_id: String) -> Result<Vec<WorldInfo>, String> {
+    let server = find_server(&server_id)?;
+
+    // Sizing every world walks all of their files
+    tokio::task::spawn_blocking(move || {
+        let server_path = Path::new(&server.path);
+        let active = level_name(&server.path);
+
+        let mut names: Vec<String> = fs::read_dir(server_path)
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .filter(|e| e.path().join("level.dat").exists())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+
+        // Bukkit keeps the nether and end as separate folders with their own level.dat
+        let all = names.clone();
+        names.retain(|n| {
+            !["_nether", "_the_end"].iter().any(|suffix| {
+                n.strip_suffix(suffix)
+                    .is_some_and(|base| all.iter().any(|w| w == base))
+            })
+        });
+
+        if !names.contains(&active) {
+            names.push(active.clone());
+        }
+
+        names.sort_by_key(|n| n.to_lowercase());
+
+        Ok(names
+            .iter()
+            .map(|name| world_info(server_path, name, *name == active))
+            .collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Points level-name at a new world, the server generates it with these settings on its next start.
+#[tauri::command]
+pub fn create_world(
+    server_id: String,
+    name: String,
+    options: NewWorldOptions,
+    state: tauri::State<'_, AppState>,
+) -> Result<WorldInfo, String> {
+    require_stopped(&state, &server_id)?;
+
+    let server = find_server(&server_id)?;
+    let server_path = Path::new(&server.path);
+    let name = name.trim().to_string();
+    require_free(server_path, &name)?;
+
+    let settings = options.generator_settings.unwrap_or_default();
+    if !settings.trim().is_empty() && serde_json::from_str::<serde_json::Value>(&settings).is_err()
+    {
+        return Err("Generator settings must be valid JSON".into());
+    }
+
+    let seed = options.seed.unwrap_or_default();
+    let level_type = options
+        .level_type
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or("minecraft:normal".into());
+
+    if [&seed, &level_type, &settings]
+        .iter()
+        .any(|v| v.contains('\n') || v.contains('\r'))
+    {
+        return Err("World settings can't contain line breaks".into());
+    }
+
+    set_server_properties(
+        &server.path,
+        &[
+            ("level-name", name.clone()),
+            ("level-seed", seed.trim().to_string()),
+            ("level-type", level_type.trim().to_string()),
+            ("generator-settings", settings.trim().to_string()),
+        ],
+    )?;
+
+    Ok(world_info(server_path, &name, true))
+}
+
+#[tauri::command]
+pub fn set_active_world(
+    server_id: String,
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    require_stopped(&state, &server_id)?;
+
+    let server = find_server(&server_id)?;
+    let name = name.trim().to_string();
+    require_world(Path::new(&server.path), &name)?;
+
+    set_server_properties(&server.path, &[("level-name", name)])
+}
+
+#[tauri::command]
+pub fn rename_world(
+    server_id: String,
+    name: String,
+    new_name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<WorldInfo, String> {
+    require_stopped(&state, &server_id)?;
+
+    let server = find_server(&server_id)?;
+    let server_path = Path::new(&server.path);
+    let name = name.trim().to_string();
+    let new_name = new_name.trim().to_string();
+
+    require_world(server_path, &name)?;
+    require_free(server_path, &new_name)?;
+
+    let mut moved: Vec<(String, String)> = Vec::new();
+    for dir in world_dir_names(server_path, &name) {
+        let target = format!("{}{}", new_name, &dir[name.len()..]);
+
+        if let Err(e) = fs::rename(server_path.join(&dir), server_path.join(&target)) {
+            // Put back what was already moved
+            for (from, to) in moved.iter().rev() {
+                fs::rename(server_path.join(to), server_path.join(from)).ok();
+            }
+            return Err(format!("Failed to rename {}: {}", dir, e));
+        }
+
+        moved.push((dir, target));
+    }
+
+    sync_level_name(server_path, &new_name);
+
+    let active = level_name(&server.path) == name;
+    if active {
+        set_server_properties(&server.path, &[("level-name", new_name.clone())])?;
+    }
+
+    Ok(world_info(server_path, &new_name, active))
+}
+
+#[tauri::command]
+pub async fn duplicate_world(
+    server_id: String,
+    name: String,
+    new_name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<WorldInfo, String> {
+    // A running server keeps writing to the world while it is copied
+    require_stopped(&state, &server_id)?;
+
+    let server = find_server(&server_id)?;
+    let name = name.trim().to_string();
+    let new_name = new_name.trim().to_string();
+
+    require_world(Path::new(&server.path), &name)?;
+    require_free(Path::new(&server.path), &new_name)?;
+
+    tokio::task::spawn_blocking(move || {
+        let server_path = Path::new(&server.path);
+
+        for dir in world_dir_names(server_path, &name) {
+            let target = server_path.join(format!("{}{}", new_name, &dir[name.len()..]));
+
+            if let Err(e) = copy_dir(&server_path.join(&dir), &target, &|rel| {
+                rel == "session.lock"
+            }) {
+                for dir in world_dir_names(server_path, &new_name) {
+                    fs::remove_dir_all(server_path.join(dir)).ok();
+                }
+                return Err(e);
+            }
+        }
+
+        sync_level_name(server_path, &new_name);
+
+        Ok(world_info(server_path, &new_name, false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn delete_world(
+    server_id: String,
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    require_stopped(&state, &server_id)?;
+
+    let server = find_server(&server_id)?;
+    let name = name.trim().to_string();
+    require_world(Path::new(&server.path), &name)?;
+
+    if level_name(&server.path) == name {
+        return Err("Switch to another world before deleting the active one".into());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let server_path = Path::new(&server.path);
+
+        for dir in world_dir_names(server_path, &name) {
+            fs::remove_dir_all(server_path.join(&dir))
+                .map_err(|e| format!("Failed to delete {}: {}", dir, e))?;
+        }
+
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,272 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::backup_manager::backup_server;
+use crate::commands::level_editor::read_spawn;
+use crate::commands::server_management::{find_server, is_server_running, level_name};
+use crate::commands::world_analysis::{
+    read_dimension_chunks, world_dimensions, ChunkInfo, Dimension,
+};
+use crate::nbt::{
+    self,
+    region::{self, REGION_CHUNKS},
+};
+use crate::state::app_state::AppState;
+use crate::utils::{archive::dir_size, path::resolve_server_file};
+
+/// TRIMMING UNUSED CHUNKS
+
+// Chunks this close to the overworld spawn are never removed
+const SPAWN_KEEP_BLOCKS: f64 = 160.0;
+
+// region/ holds the terrain, entities/ and poi/ (1.14+ / 1.17+) use the same layout
+const REGION_FOLDERS: [&str; 3] = ["region", "entities", "poi"];
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct TrimOptions {
+    // Chunks further than this from spawn are removed (the nether uses spawn / 8, other dimensions 0, 0)
+    pub radius_blocks: Option<u32>,
+    // Chunks players have spent fewer ticks in are removed
+    pub min_inhabited_ticks: Option<i64>,
+    // Dimension ids to trim, all of them when empty
+    #[serde(default)]
+    pub dimensions: Vec<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DimensionTrim {
+    pub id: String,
+    pub chunk_count: usize,
+    pub chunks_removed: usize,
+    pub size_before: u64,
+    pub size_after: u64, // an estimate for dry runs
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrimReport {
+    pub dry_run: bool,
+    pub backup_id: Option<String>,
+    pub dimensions: Vec<DimensionTrim>,
+    pub chunks_removed: usize,
+    pub reclaimed_bytes: u64,
+}
+
+fn trim_center(dimension: &str, spawn: (i32, i32)) -> (f64, f64) {
+    match dimension {
+        "minecraft:overworld" => (spawn.0 as f64, spawn.1 as f64),
+        "minecraft:the_nether" => (spawn.0 as f64 / 8.0, spawn.1 as f64 / 8.0),
+        _ => (0.0, 0.0),
+    }
+}
+
+fn should_remove(
+    chunk: &ChunkInfo,
+    center: (f64, f64),
+    overworld: bool,
+    options: &TrimOptions,
+) -> bool {
+    // Distance from the middle of the chunk
+    let dx = (chunk.x * 16 + 8) as f64 - center.0;
+    let dz = (chunk.z * 16 + 8) as f64 - center.1;
+    let distance = (dx * dx + dz * dz).sqrt();
+
+    if overworld && distance <= SPAWN_KEEP_BLOCKS {
+        return false;
+    }
+
+    let outside = options.radius_blocks.is_some_and(|r| distance > r as f64);
+
+    // Chunks that couldn't be read are kept, we don't know how much they were used
+    let unused = options
+        .min_inhabited_ticks
+        .is_some_and(|min| chunk.inhabited_ticks.is_some_and(|t| t < min));
+
+    outside || unused
+}
+
+fn folders_size(dir: &Path) -> u64 {
+    REGION_FOLDERS.iter().map(|f| dir_size(&dir.join(f))).sum()
+}
+
+struct TrimPlan {
+    dimension: Dimension,
+    chunk_count: usize,
+    chunks_removed: usize,
+    removed_bytes: u64,
+    // Chunk coordinates inside their region, grouped by region
+    by_region: HashMap<(i32, i32), HashSet<(i32, i32)>>,
+}
+
+fn plan_trim(
+    dimensions: Vec<Dimension>,
+    spawn: (i32, i32),
+    options: &TrimOptions,
+) -> Vec<TrimPlan> {
+    let mut plans = Vec::new();
+
+    for dimension in dimensions {
+        let (_, chunks) = read_dimension_chunks(&dimension.dir);
+        let center = trim_center(&dimension.id, spawn);
+        let overworld = dimension.id == "minecraft:overworld";
+
+        let removed: Vec<&ChunkInfo> = chunks
+            .iter()
+            .filter(|c| should_remove(c, center, overworld, options))
+            .collect();
+
+        let mut by_region: HashMap<(i32, i32), HashSet<(i32, i32)>> = HashMap::new();
+        for chunk in &removed {
+            by_region
+                .entry((
+                    chunk.x.div_euclid(REGION_CHUNKS),
+                    chunk.z.div_euclid(REGION_CHUNKS),
+                ))
+                .or_default()
+                .insert((
+                    chunk.x.rem_euclid(REGION_CHUNKS),
+                    chunk.z.rem_euclid(REGION_CHUNKS),
+                ));
+        }
+
+        plans.push(TrimPlan {
+            dimension,
+            chunk_count: chunks.len(),
+            chunks_removed: removed.len(),
+            removed_bytes: removed.iter().map(|c| c.size_bytes).sum(),
+            by_region,
+        });
+    }
+
+    plans
+}
+
+fn apply_trim(
+    plans: Vec<TrimPlan>,
+    dry_run: bool,
+    backup_id: Option<String>,
+) -> Result<TrimReport, String> {
+    let mut report = TrimReport {
+        dry_run,
+        backup_id,
+        dimensions: Vec::new(),
+        chunks_removed: 0,
+        reclaimed_bytes: 0,
+    };
+
+    for plan in plans {
+        let dir = &plan.dimension.dir;
+        let size_before = folders_size(dir);
+
+        if !dry_run {
+            for ((rx, rz), chunks) in &plan.by_region {
+                let keep = |l: &region::ChunkLocation| !chunks.contains(&(l.local_x, l.local_z));
+
+                for folder in REGION_FOLDERS {
+                    let path = dir.join(folder).join(format!("r.{}.{}.mca", rx, rz));
+
+                    if path.exists() {
+                        region::retain_chunks(&path, (*rx, *rz), &keep).map_err(|e| {
+                            format!(
+                                "Failed to trim {}: {} (backup {} has the world as it was)",
+                                path.display(),
+                                e,
+                                report.backup_id.as_deref().unwrap_or_default()
+                            )
+                        })?;
+                    }
+                }
+            }
+        }
+
+        let size_after = if dry_run {
+            size_before.saturating_sub(plan.removed_bytes)
+        } else {
+            folders_size(dir)
+        };
+
+        report.chunks_removed += plan.chunks_removed;
+        report.reclaimed_bytes += size_before.saturating_sub(size_after);
+        report.dimensions.push(DimensionTrim {
+            id: plan.dimension.id,
+            chunk_count: plan.chunk_count,
+            chunks_removed: plan.chunks_removed,
+            size_before,
+            size_after,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Removes chunks outside a radius or barely visited, after backing the server up.
+///
+/// Only runs while the server is stopped. With `dry_run` nothing is backed up or
+/// changed and the report shows what would be removed.
+#[tauri::command]
+pub async fn trim_world(
+    server_id: String,
+    world: Option<String>,
+    options: TrimOptions,
+    backup_passphrase: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<TrimReport, String> {
+    if options.radius_blocks.is_none() && options.min_inhabited_ticks.is_none() {
+        return Err("Choose a radius, a minimum inhabited time, or both".into());
+    }
+
+    if options.min_inhabited_ticks.is_some_and(|t| t < 0) {
+        return Err("Minimum inhabited time can't be negative".into());
+    }
+
+    if !options.dry_run && is_server_running(&state, &server_id) {
+        return Err("Stop the server before trimming the world".into());
+    }
+
+    let server = find_server(&server_id)?;
+    let world = world.unwrap_or_else(|| level_name(&server.path));
+
+    let level = nbt::read_file(&resolve_server_file(
+        &server.path,
+        &format!("{}/level.dat", world),
+    )?)?;
+    let (spawn_x, _, spawn_z) = level
+        .root
+        .get("Data")
+        .map(read_spawn)
+        .ok_or("level.dat has no Data compound")?;
+
+    let dimensions: Vec<_> = world_dimensions(Path::new(&server.path), &world)
+        .into_iter()
+        .filter(|d| options.dimensions.is_empty() || options.dimensions.contains(&d.id))
+        .collect();
+
+    let dry_run = options.dry_run;
+
+    // Plan first, so the backup is only made when there is something to remove. Every
+    // chunk gets decompressed, far too slow for the async runtime
+    let plans =
+        tokio::task::spawn_blocking(move || plan_trim(dimensions, (spawn_x, spawn_z), &options))
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let total_removed: usize = plans.iter().map(|p| p.chunks_removed).sum();
+
+    let backup_id = if dry_run || total_removed == 0 {
+        None
+    } else {
+        let info = backup_server(&server, &state, backup_passphrase.as_deref())
+            .await
+            .map_err(|e| format!("Backup before trimming failed, nothing was changed: {}", e))?;
+        Some(info.id)
+    };
+
+    tokio::task::spawn_blocking(move || apply_trim(plans, dry_run, backup_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -1,12 +1,37 @@
 #![allow(unused)]
 
 pub mod commands;
+pub mod nbt;
 pub mod state;
 pub mod utils;
 
+use crate::commands::backup_manager::{
+    browse_backup, create_backup, delete_backup, list_backups, restore_backup,
+    restore_backup_as_new, restore_backup_entries,
+};
+use crate::commands::datapacks::{
+    install_datapack, list_datapacks, remove_datapack, set_datapack_enabled,
+};
 use crate::commands::discord_rpc::{discord_set_server_running, init_discord_rpc, set_idle, clear_rpc};
+use crate::commands::eula::{accept_eula, get_eula_acceptance};
+use crate::commands::level_editor::{read_level_dat, update_level_dat};
 use crate::commands::misc::open_folder;
+use crate::commands::motd::{build_motd, preview_motd};
+use crate::commands::nbt_viewer::{read_nbt_file, read_nbt_file_as_snbt, write_nbt_file};
+use crate::commands::player_data::{list_player_data, read_player_data, update_player_data};
+use crate::commands::player_stats::{get_player_stats, get_stats_leaderboard};
+use crate::commands::players::resolve_player_uuid;
+use crate::commands::properties_schema::{check_server_properties, get_properties_schema};
+use crate::commands::remote_backups::{
+    delete_backup_destination, download_remote_backup, get_backup_destinations,
+    list_remote_backups, save_backup_destination, test_backup_destination, upload_backup,
+};
+use crate::commands::resource_packs::{get_resource_pack, host_resource_pack, remove_resource_pack};
 use crate::commands::server_creation::create_server;
+use crate::commands::server_icon::{remove_server_icon, set_server_icon};
+use crate::commands::server_launch::{
+    get_system_memory_mb, preview_launch_command, read_launch_config, update_launch_config,
+};
 use crate::commands::server_management::delete_server;
 use crate::commands::server_management::get_active_server;
 use crate::commands::server_management::list_servers;
@@ -17,12 +42,22 @@ use crate::commands::server_management::start_server;
 use crate::commands::server_management::stop_server;
 use crate::commands::server_management::update_server_config;
 use crate::commands::server_management::update_server_properties;
+use crate::commands::server_management::{
+    get_server_property, read_all_server_properties, set_server_property_values,
+};
 use crate::commands::server_management::check_world_exists;
 use crate::commands::versions_loaders::fetch_fabric_versions;
 use crate::commands::versions_loaders::fetch_forge_versions;
 use crate::commands::versions_loaders::get_mc_versions;
 use crate::commands::versions_loaders::get_supported_loaders;
 use crate::commands::versions_loaders::LoaderSupportCache;
+use crate::commands::world_analysis::{analyze_world, read_region_chunks};
+use crate::commands::world_export::export_world;
+use crate::commands::world_import::{check_world_import, import_world, list_singleplayer_worlds};
+use crate::commands::world_manager::{
+    create_world, delete_world, duplicate_world, list_worlds, rename_world, set_active_world,
+};
+use crate::commands::world_trim::trim_world;
 use crate::state::app_state::AppState;
 use tauri::Manager;
 
@@ -140,6 +175,20 @@ pub fn run() {
             list_servers,
             read_server_properties,
             update_server_properties,
+            read_all_server_properties,
+            get_server_property,
+            set_server_property_values,
+            get_properties_schema,
+            check_server_properties,
+            preview_motd,
+            build_motd,
+            accept_eula,
+            get_eula_acceptance,
+            get_system_memory_mb,
+            read_launch_config,
+            update_launch_config,
+            preview_launch_command,
+            write_nbt_file,
             get_active_server,
             start_server,
             stop_server,
@@ -151,7 +200,53 @@ pub fn run() {
             discord_set_server_running,
             set_idle,
             clear_rpc,
-            check_world_exists
+            check_world_exists,
+            create_backup,
+            list_backups,
+            delete_backup,
+            restore_backup,
+            restore_backup_as_new,
+            browse_backup,
+            restore_backup_entries,
+            get_backup_destinations,
+            save_backup_destination,
+            delete_backup_destination,
+            test_backup_destination,
+            upload_backup,
+            list_remote_backups,
+            download_remote_backup,
+            read_nbt_file,
+            read_nbt_file_as_snbt,
+            read_level_dat,
+            update_level_dat,
+            resolve_player_uuid,
+            list_player_data,
+            read_player_data,
+            update_player_data,
+            get_player_stats,
+            get_stats_leaderboard,
+            analyze_world,
+            read_region_chunks,
+            trim_world,
+            list_worlds,
+            create_world,
+            set_active_world,
+            rename_world,
+            duplicate_world,
+            delete_world,
+            list_singleplayer_worlds,
+            check_world_import,
+            import_world,
+            export_world,
+            list_datapacks,
+            install_datapack,
+            set_datapack_enabled,
+            remove_datapack,
+            host_resource_pack,
+            get_resource_pack,
+            remove_resource_pack,
+            set_server_icon,
+            remove_server_icon
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,475 @@
+use super::*;
+
+// Same nesting limit as the game, protects against stack overflows on hostile files
+const MAX_DEPTH: usize = 512;
+
+/// Big-endian NBT reader over an in-memory buffer.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.data.len() - self.pos < n {
+            return Err("Unexpected end of NBT data".into());
+        }
+
+        let slice = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        Ok(i16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64, String> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    // Array / list lengths are signed ints, reject negative and impossible ones up front
+    fn len(&mut self, item_size: usize) -> Result<usize, String> {
+        let len = self.i32()?;
+
+        if len < 0 || (len as usize).saturating_mul(item_size) > self.data.len() - self.pos {
+            return Err(format!("Invalid NBT length {}", len));
+        }
+
+        Ok(len as usize)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as usize;
+        decode_mutf8(self.take(len)?)
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> Result<Tag, String> {
+        if depth > MAX_DEPTH {
+            return Err("NBT is nested too deeply".into());
+        }
+
+        Ok(match id {
+            TAG_BYTE => Tag::Byte(self.u8()? as i8),
+            TAG_SHORT => Tag::Short(self.i16()?),
+            TAG_INT => Tag::Int(self.i32()?),
+            TAG_LONG => Tag::Long(self.i64()?),
+            TAG_FLOAT => Tag::Float(f32::from_bits(self.i32()? as u32)),
+            TAG_DOUBLE => Tag::Double(f64::from_bits(self.i64()? as u64)),
+            TAG_BYTE_ARRAY => {
+                let len = self.len(1)?;
+                Tag::ByteArray(self.take(len)?.iter().map(|b| *b as i8).collect())
+            }
+            TAG_STRING => Tag::String(self.string()?),
+            TAG_LIST => {
+                let element = self.u8()?;
+                // Every payload is at least one byte long
+                let len = self.len(1)?;
+
+                // An empty list may still carry any element type (usually End)
+                if len > 0 && (element == TAG_END || element > TAG_LONG_ARRAY) {
+                    return Err(format!("Invalid NBT list element type {}", element));
+                }
+
+                let mut items = Vec::with_capacity(len.min(4096));
+                for _ in 0..len {
+                    items.push(self.payload(element, depth + 1)?);
+                }
+
+                Tag::List { element, items }
+            }
+            TAG_COMPOUND => {
+                let mut entries = Vec::new();
+
+                loop {
+                    let child = self.u8()?;
+                    if child == TAG_END {
+                        break;
+                    }
+
+                    let name = self.string()?;
+                    entries.push((name, self.payload(child, depth + 1)?));
+                }
+
+                Tag::Compound(entries)
+            }
+            TAG_INT_ARRAY => {
+                let len = self.len(4)?;
+                (0..len)
+                    .map(|_| self.i32())
+                    .collect::<Result<_, _>>()
+                    .map(Tag::IntArray)?
+            }
+            TAG_LONG_ARRAY => {
+                let len = self.len(8)?;
+                (0..len)
+                    .map(|_| self.i64())
+                    .collect::<Result<_, _>>()
+                    .map(Tag::LongArray)?
+            }
+            other => return Err(format!("Unknown NBT tag type {}", other)),
+        })
+    }
+}
+
+/// Reads an uncompressed named root tag (the layout of every NBT file).
+pub fn read_named(data: &[u8]) -> Result<(String, Tag), String> {
+    let mut reader = Reader { data, pos: 0 };
+
+    let id = reader.u8()?;
+    if id != TAG_COMPOUND {
+        return Err(format!(
+            "NBT root must be a compound, found tag type {}",
+            id
+        ));
+    }
+
+    let name = reader.string()?;
+    let tag = reader.payload(id, 0)?;
+
+    Ok((name, tag))
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) -> Result<(), String> {
+    let bytes = encode_mutf8(value);
+    // The length is a u16, the game refuses to write longer strings as well
+    let len = u16::try_from(bytes.len()).map_err(|_| {
+        format!(
+            "NBT string is {} bytes long, the limit is {}",
+            bytes.len(),
+            u16::MAX
+        )
+    })?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(&bytes);
+    Ok(())
+}
+
+fn write_payload(out: &mut Vec<u8>, tag: &Tag) -> Result<(), String> {
+    match tag {
+        Tag::Byte(v) => out.push(*v as u8),
+        Tag::Short(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Float(v) => out.extend_from_slice(&v.to_bits().to_be_bytes()),
+        Tag::Double(v) => out.extend_from_slice(&v.to_bits().to_be_bytes()),
+        Tag::ByteArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            out.extend(values.iter().map(|v| *v as u8));
+        }
+        Tag::String(v) => write_string(out, v)?,
+        Tag::List { element, items } => {
+            let element = items.first().map(|t| t.id()).unwrap_or(*element);
+            out.push(element);
+            out.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            for item in items {
+                write_payload(out, item)?;
+            }
+        }
+        Tag::Compound(entries) => {
+            for (name, value) in entries {
+                out.push(value.id());
+                write_string(out, name)?;
+                write_payload(out, value)?;
+            }
+            out.push(TAG_END);
+        }
+        Tag::IntArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            for v in values {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Tag::LongArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            for v in values {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes an uncompressed named root tag.
+pub fn write_named(name: &str, tag: &Tag) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    out.push(tag.id());
+    write_string(&mut out, name)?;
+    write_payload(&mut out, tag)?;
+    Ok(out)
+}
+
+/// Java's "modified UTF-8": NUL is two bytes and characters outside the BMP are
+/// written as two 3-byte surrogates instead of one 4-byte sequence.
+pub fn decode_mutf8(bytes: &[u8]) -> Result<String, String> {
+    // Fast path, almost every key and value is plain ASCII
+    if bytes.iter().all(|b| *b != 0 && *b < 0x80) {
+        return Ok(String::from_utf8(bytes.to_vec()).unwrap());
+    }
+
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i] as u16;
+
+        let (unit, width) = if b < 0x80 {
+            (b, 1)
+        } else if b & 0xE0 == 0xC0 && i + 1 < bytes.len() {
+            (((b & 0x1F) << 6) | (bytes[i + 1] as u16 & 0x3F), 2)
+        } else if b & 0xF0 == 0xE0 && i + 2 < bytes.len() {
+            (
+                ((b & 0x0F) << 12)
+                    | ((bytes[i + 1] as u16 & 0x3F) << 6)
+                    | (bytes[i + 2] as u16 & 0x3F),
+                3,
+            )
+        } else {
+            return Err("Invalid modified UTF-8 in NBT string".into());
+        };
+
+        units.push(unit);
+        i += width;
+    }
+
+    Ok(String::from_utf16_lossy(&units))
+}
+
+pub fn encode_mutf8(value: &str) -> Vec<u8> {
+    if value.bytes().all(|b| b != 0 && b < 0x80) {
+        return value.as_bytes().to_vec();
+    }
+
+    let mut out = Vec::with_capacity(value.len() + 8);
+
+    for unit in value.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => out.push(unit as u8),
+            0x0000 | 0x0080..=0x07FF => {
+                out.push(0xC0 | (unit >> 6) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                out.push(0xE0 | (unit >> 12) as u8);
+                out.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // hello_world.nbt from the NBT specification
+    const HELLO_WORLD: &[u8] = b"\x0a\x00\x0bhello world\x08\x00\x04name\x00\x09Bananrama\x00";
+
+    fn compress(raw: &[u8], compression: Compression) -> Vec<u8> {
+        match compression {
+            Compression::None => raw.to_vec(),
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(raw).unwrap();
+                encoder.finish().unwrap()
+            }
+            Compression::Zlib => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(raw).unwrap();
+                encoder.finish().unwrap()
+            }
+        }
+    }
+
+    #[test]
+    fn reads_and_writes_the_spec_sample() {
+        let (name, root) = read_named(HELLO_WORLD).unwrap();
+
+        assert_eq!(name, "hello world");
+        assert_eq!(
+            root,
+            Tag::Compound(vec![("name".into(), Tag::String("Bananrama".into()))])
+        );
+        assert_eq!(write_named(&name, &root).unwrap(), HELLO_WORLD);
+    }
+
+    const BYTE_ARRAY_NAME: &str = "byteArrayTest (the first 1000 values of (n*n*255+n*7)%100, starting with n=0 (0, 62, 34, 16, 8, ...))";
+
+    // bigtest.nbt, the classic test file shipped with the NBT specification, laid out byte
+    // for byte so the reader is checked against a file the writer didn't produce
+    fn bigtest() -> Vec<u8> {
+        fn named(out: &mut Vec<u8>, id: u8, name: &str) {
+            out.push(id);
+            out.extend((name.len() as u16).to_be_bytes());
+            out.extend(name.as_bytes());
+        }
+
+        fn string(out: &mut Vec<u8>, bytes: &[u8]) {
+            out.extend((bytes.len() as u16).to_be_bytes());
+            out.extend(bytes);
+        }
+
+        let mut out = Vec::new();
+        named(&mut out, TAG_COMPOUND, "Level");
+
+        named(&mut out, TAG_LONG, "longTest");
+        out.extend(i64::MAX.to_be_bytes());
+        named(&mut out, TAG_SHORT, "shortTest");
+        out.extend(i16::MAX.to_be_bytes());
+        named(&mut out, TAG_STRING, "stringTest");
+        // Å, Ä and Ö are two bytes each
+        string(
+            &mut out,
+            b"HELLO WORLD THIS IS A TEST STRING \xC3\x85\xC3\x84\xC3\x96!",
+        );
+        named(&mut out, TAG_FLOAT, "floatTest");
+        out.extend(0.498_231_47_f32.to_be_bytes());
+        named(&mut out, TAG_INT, "intTest");
+        out.extend(i32::MAX.to_be_bytes());
+
+        named(&mut out, TAG_COMPOUND, "nested compound test");
+        for (key, name, value) in [("ham", "Hampus", 0.75_f32), ("egg", "Eggbert", 0.5)] {
+            named(&mut out, TAG_COMPOUND, key);
+            named(&mut out, TAG_STRING, "name");
+            string(&mut out, name.as_bytes());
+            named(&mut out, TAG_FLOAT, "value");
+            out.extend(value.to_be_bytes());
+            out.push(TAG_END);
+        }
+        out.push(TAG_END);
+
+        named(&mut out, TAG_LIST, "listTest (long)");
+        out.push(TAG_LONG);
+        out.extend(5_i32.to_be_bytes());
+        for v in 11_i64..=15 {
+            out.extend(v.to_be_bytes());
+        }
+
+        named(&mut out, TAG_LIST, "listTest (compound)");
+        out.push(TAG_COMPOUND);
+        out.extend(2_i32.to_be_bytes());
+        for i in 0..2 {
+            named(&mut out, TAG_STRING, "name");
+            string(&mut out, format!("Compound tag #{}", i).as_bytes());
+            named(&mut out, TAG_LONG, "created-on");
+            out.extend(1264099775885_i64.to_be_bytes());
+            out.push(TAG_END);
+        }
+
+        named(&mut out, TAG_BYTE, "byteTest");
+        out.push(127);
+
+        named(&mut out, TAG_BYTE_ARRAY, BYTE_ARRAY_NAME);
+        out.extend(1000_i32.to_be_bytes());
+        out.extend((0..1000_u32).map(|n| ((n * n * 255 + n * 7) % 100) as u8));
+
+        named(&mut out, TAG_DOUBLE, "doubleTest");
+        out.extend(0.493_128_713_218_231_5_f64.to_be_bytes());
+
+        out.push(TAG_END);
+        out
+    }
+
+    #[test]
+    fn reads_and_writes_bigtest() {
+        let raw = bigtest();
+        // Shipped gzipped, like level.dat
+        let file = from_bytes(&compress(&raw, Compression::Gzip)).unwrap();
+
+        assert_eq!(file.name, "Level");
+        assert_eq!(file.compression, Compression::Gzip);
+
+        let root = &file.root;
+        assert_eq!(root.get("longTest"), Some(&Tag::Long(i64::MAX)));
+        assert_eq!(root.get("shortTest"), Some(&Tag::Short(i16::MAX)));
+        assert_eq!(
+            root.get("stringTest"),
+            Some(&Tag::String(
+                "HELLO WORLD THIS IS A TEST STRING \u{c5}\u{c4}\u{d6}!".into()
+            ))
+        );
+        assert_eq!(
+            root.get("nested compound test")
+                .and_then(|t| t.get("egg"))
+                .and_then(|t| t.get("name")),
+            Some(&Tag::String("Eggbert".into()))
+        );
+        assert_eq!(
+            root.get("listTest (long)"),
+            Some(&Tag::List {
+                element: TAG_LONG,
+                items: (11..=15).map(Tag::Long).collect(),
+            })
+        );
+        match root.get(BYTE_ARRAY_NAME) {
+            Some(Tag::ByteArray(values)) => {
+                assert_eq!(values.len(), 1000);
+                assert_eq!(values[..5], [0, 62, 34, 16, 8]);
+            }
+            other => panic!("unexpected byte array {:?}", other),
+        }
+
+        // Written back with the same layout, byte for byte
+        assert_eq!(write_named(&file.name, &file.root).unwrap(), raw);
+        assert_eq!(from_bytes(&to_bytes(&file).unwrap()).unwrap(), file);
+    }
+
+    #[test]
+    fn refuses_strings_longer_than_the_length_prefix() {
+        let fits = Tag::String("a".repeat(u16::MAX as usize));
+        assert!(write_named("", &Tag::Compound(vec![("s".into(), fits)])).is_ok());
+
+        // Counted in encoded bytes, each é takes two
+        let long = Tag::String("\u{e9}".repeat(40000));
+        assert!(write_named("", &Tag::Compound(vec![("s".into(), long)])).is_err());
+    }
+
+    #[test]
+    fn round_trips_every_compression() {
+        let sample = write_named("", &test_sample()).unwrap();
+
+        for raw in [HELLO_WORLD.to_vec(), sample] {
+            for compression in [Compression::Gzip, Compression::Zlib, Compression::None] {
+                let file = from_bytes(&compress(&raw, compression)).unwrap();
+                assert_eq!(file.compression, compression);
+
+                // Written back with the same compression, and byte for byte once decompressed
+                let written = to_bytes(&file).unwrap();
+                assert_eq!(from_bytes(&written).unwrap(), file);
+                assert_eq!(write_named(&file.name, &file.root).unwrap(), raw);
+            }
+        }
+    }
+
+    #[test]
+    fn modified_utf8() {
+        // NUL as two bytes, the emoji as a surrogate pair of two 3-byte sequences
+        let text = "a\0\u{1F600}\u{e9}";
+        let encoded = [
+            0x61, 0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80, 0xC3, 0xA9,
+        ];
+
+        assert_eq!(encode_mutf8(text), encoded);
+        assert_eq!(decode_mutf8(&encoded).unwrap(), text);
+        assert_eq!(encode_mutf8("plain"), b"plain");
+        assert!(decode_mutf8(&[0xE0, 0x80]).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        for len in 0..HELLO_WORLD.len() {
+            assert!(read_named(&HELLO_WORLD[..len]).is_err());
+        }
+    }
+}
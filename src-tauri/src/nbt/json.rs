@@ -0,0 +1,200 @@
+use serde_json::{json, Map, Number, Value};
+
+use super::*;
+
+// The JSON tree sent to the frontend. Every node is `{ "type": ..., "value": ... }`,
+// compound entries additionally carry their `name` and lists their `element` type.
+// Longs are sent as strings because JavaScript numbers can't hold them (world seeds!).
+
+pub fn type_name(id: u8) -> &'static str {
+    match id {
+        TAG_BYTE => "byte",
+        TAG_SHORT => "short",
+        TAG_INT => "int",
+        TAG_LONG => "long",
+        TAG_FLOAT => "float",
+        TAG_DOUBLE => "double",
+        TAG_BYTE_ARRAY => "byte_array",
+        TAG_STRING => "string",
+        TAG_LIST => "list",
+        TAG_COMPOUND => "compound",
+        TAG_INT_ARRAY => "int_array",
+        TAG_LONG_ARRAY => "long_array",
+        _ => "end",
+    }
+}
+
+fn type_id(name: &str) -> Option<u8> {
+    (TAG_END..=TAG_LONG_ARRAY).find(|id| type_name(*id) == name)
+}
+
+fn float_value(v: f64) -> Value {
+    Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(v.to_string()))
+}
+
+pub fn to_json(tag: &Tag) -> Value {
+    let value = match tag {
+        Tag::Byte(v) => json!(v),
+        Tag::Short(v) => json!(v),
+        Tag::Int(v) => json!(v),
+        Tag::Long(v) => json!(v.to_string()),
+        Tag::Float(v) => float_value(*v as f64),
+        Tag::Double(v) => float_value(*v),
+        Tag::ByteArray(values) => json!(values),
+        Tag::String(v) => json!(v),
+        Tag::List { items, .. } => Value::Array(items.iter().map(to_json).collect()),
+        Tag::Compound(entries) => Value::Array(
+            entries
+                .iter()
+                .map(|(name, child)| {
+                    let mut node = to_json(child);
+                    node["name"] = json!(name);
+                    node
+                })
+                .collect(),
+        ),
+        Tag::IntArray(values) => json!(values),
+        Tag::LongArray(values) => json!(values.iter().map(|v| v.to_string()).collect::<Vec<_>>()),
+    };
+
+    let mut node = Map::new();
+    node.insert("type".into(), json!(type_name(tag.id())));
+    node.insert("value".into(), value);
+
+    if let Tag::List { element, items } = tag {
+        let element = items.first().map(|t| t.id()).unwrap_or(*element);
+        node.insert("element".into(), json!(type_name(element)));
+    }
+
+    Value::Object(node)
+}
+
+fn int_of(value: &Value) -> Result<i64, String> {
+    match value {
+        Value::Number(n) => n.as_i64().ok_or(format!("{} is not an integer", n)),
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| format!("{} is not an integer", s)),
+        other => Err(format!("{} is not an integer", other)),
+    }
+}
+
+fn float_of(value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Number(n) => n.as_f64().ok_or(format!("{} is not a number", n)),
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| format!("{} is not a number", s)),
+        other => Err(format!("{} is not a number", other)),
+    }
+}
+
+// Range checked narrowing so an edited 300 never silently becomes a byte of 44
+fn narrow<T: TryFrom<i64>>(value: &Value, kind: &str) -> Result<T, String> {
+    let n = int_of(value)?;
+    T::try_from(n).map_err(|_| format!("{} is out of range for a {}", n, kind))
+}
+
+fn array_of(value: &Value) -> Result<&Vec<Value>, String> {
+    value.as_array().ok_or("Expected an array".to_string())
+}
+
+/// Converts a JSON node back into NBT, validating every value against its type.
+pub fn from_json(node: &Value) -> Result<Tag, String> {
+    let kind = node["type"].as_str().ok_or("NBT node without a type")?;
+    let value = &node["value"];
+
+    Ok(match kind {
+        "byte" => Tag::Byte(narrow(value, kind)?),
+        "short" => Tag::Short(narrow(value, kind)?),
+        "int" => Tag::Int(narrow(value, kind)?),
+        "long" => Tag::Long(int_of(value)?),
+        "float" => Tag::Float(float_of(value)? as f32),
+        "double" => Tag::Double(float_of(value)?),
+        "string" => Tag::String(value.as_str().ok_or("Expected a string")?.to_string()),
+        "byte_array" => Tag::ByteArray(
+            array_of(value)?
+                .iter()
+                .map(|v| narrow(v, "byte"))
+                .collect::<Result<_, _>>()?,
+        ),
+        "int_array" => Tag::IntArray(
+            array_of(value)?
+                .iter()
+                .map(|v| narrow(v, "int"))
+                .collect::<Result<_, _>>()?,
+        ),
+        "long_array" => Tag::LongArray(
+            array_of(value)?
+                .iter()
+                .map(int_of)
+                .collect::<Result<_, _>>()?,
+        ),
+        "list" => {
+            let items = array_of(value)?
+                .iter()
+                .map(from_json)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let element = match items.first() {
+                Some(first) => first.id(),
+                None => node["element"]
+                    .as_str()
+                    .and_then(type_id)
+                    .unwrap_or(TAG_END),
+            };
+
+            if items.iter().any(|t| t.id() != element) {
+                return Err("All elements of a list must have the same type".into());
+            }
+
+            Tag::List { element, items }
+        }
+        "compound" => {
+            let mut entries = Vec::new();
+
+            for child in array_of(value)? {
+                let name = child["name"]
+                    .as_str()
+                    .ok_or("Compound entry without a name")?;
+                entries.push((name.to_string(), from_json(child)?));
+            }
+
+            Tag::Compound(entries)
+        }
+        other => return Err(format!("Unknown NBT type {}", other)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let sample = test_sample();
+        assert_eq!(from_json(&to_json(&sample)).unwrap(), sample);
+    }
+
+    #[test]
+    fn longs_are_strings() {
+        let node = to_json(&Tag::Long(i64::MIN));
+
+        assert_eq!(node["value"], Value::String(i64::MIN.to_string()));
+        assert_eq!(from_json(&node).unwrap(), Tag::Long(i64::MIN));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(from_json(&json!({ "type": "byte", "value": 300 })).is_err());
+        assert!(from_json(&json!({
+            "type": "list",
+            "value": [{ "type": "byte", "value": 1 }, { "type": "int", "value": 1 }]
+        }))
+        .is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::Path,
+};
+
+use flate2::{
+    read::{GzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+};
+use serde::{Deserialize, Serialize};
+
+pub mod binary;
+pub mod json;
+pub mod region;
+pub mod snbt;
+
+/// NBT (Named Binary Tag), the format Minecraft stores worlds and player data in.
+///
+/// Compounds keep their entries in file order so that reading and writing a file
+/// without changes produces the same bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    // `element` is the tag id of the items, kept for empty lists
+    List { element: u8, items: Vec<Tag> },
+    Compound(Vec<(String, Tag)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+pub const TAG_END: u8 = 0;
+pub const TAG_BYTE: u8 = 1;
+pub const TAG_SHORT: u8 = 2;
+pub const TAG_INT: u8 = 3;
+pub const TAG_LONG: u8 = 4;
+pub const TAG_FLOAT: u8 = 5;
+pub const TAG_DOUBLE: u8 = 6;
+pub const TAG_BYTE_ARRAY: u8 = 7;
+pub const TAG_STRING: u8 = 8;
+pub const TAG_LIST: u8 = 9;
+pub const TAG_COMPOUND: u8 = 10;
+pub const TAG_INT_ARRAY: u8 = 11;
+pub const TAG_LONG_ARRAY: u8 = 12;
+
+impl Tag {
+    pub fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => TAG_BYTE,
+            Tag::Short(_) => TAG_SHORT,
+            Tag::Int(_) => TAG_INT,
+            Tag::Long(_) => TAG_LONG,
+            Tag::Float(_) => TAG_FLOAT,
+            Tag::Double(_) => TAG_DOUBLE,
+            Tag::ByteArray(_) => TAG_BYTE_ARRAY,
+            Tag::String(_) => TAG_STRING,
+            Tag::List { .. } => TAG_LIST,
+            Tag::Compound(_) => TAG_COMPOUND,
+            Tag::IntArray(_) => TAG_INT_ARRAY,
+            Tag::LongArray(_) => TAG_LONG_ARRAY,
+        }
+    }
+
+    /// Looks up a direct child of a compound.
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        match self {
+            Tag::Compound(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Tag> {
+        match self {
+            Tag::Compound(entries) => entries.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Follows a `/` separated path of compound keys, e.g. `Data/WorldGenSettings/seed`.
+    pub fn get_path(&self, path: &str) -> Option<&Tag> {
+        path.split('/').try_fold(self, |tag, key| tag.get(key))
+    }
+
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Tag> {
+        path.split('/').try_fold(self, |tag, key| tag.get_mut(key))
+    }
+
+    /// Sets a compound entry, replacing it in place or appending it at the end.
+    /// Does nothing when `self` is not a compound.
+    pub fn insert(&mut self, key: &str, value: Tag) {
+        if let Tag::Compound(entries) = self {
+            match entries.iter_mut().find(|(k, _)| k == key) {
+                Some((_, existing)) => *existing = value,
+                None => entries.push((key.to_string(), value)),
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Tag> {
+        match self {
+            Tag::Compound(entries) => {
+                let index = entries.iter().position(|(k, _)| k == key)?;
+                Some(entries.remove(index).1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Any numeric tag widened to i64 (floating point values are truncated).
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Tag::Byte(v) => Some(*v as i64),
+            Tag::Short(v) => Some(*v as i64),
+            Tag::Int(v) => Some(*v as i64),
+            Tag::Long(v) => Some(*v),
+            Tag::Float(v) => Some(*v as i64),
+            Tag::Double(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Tag::Float(v) => Some(*v as f64),
+            Tag::Double(v) => Some(*v),
+            other => other.as_i64().map(|v| v as f64),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&Vec<Tag>> {
+        match self {
+            Tag::List { items, .. } => Some(items),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    Gzip,
+    Zlib,
+    None,
+}
+
+/// A whole NBT file: the (usually empty) root name, the root compound and how it was compressed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbtFile {
+    pub name: String,
+    pub root: Tag,
+    pub compression: Compression,
+}
+
+/// Detects gzip / zlib / raw NBT and decodes it.
+pub fn from_bytes(bytes: &[u8]) -> Result<NbtFile, String> {
+    let (compression, raw) = match bytes {
+        [0x1f, 0x8b, ..] => {
+            let mut out = Vec::new();
+            GzDecoder::new(bytes)
+                .read_to_end(&mut out)
+                .map_err(|e| format!("Invalid gzip data: {}", e))?;
+            (Compression::Gzip, out)
+        }
+        [0x78, ..] => {
+            let mut out = Vec::new();
+            ZlibDecoder::new(bytes)
+                .read_to_end(&mut out)
+                .map_err(|e| format!("Invalid zlib data: {}", e))?;
+            (Compression::Zlib, out)
+        }
+        _ => (Compression::None, bytes.to_vec()),
+    };
+
+    let (name, root) = binary::read_named(&raw)?;
+
+    Ok(NbtFile {
+        name,
+        root,
+        compression,
+    })
+}
+
+pub fn to_bytes(file: &NbtFile) -> Result<Vec<u8>, String> {
+    let raw = binary::write_named(&file.name, &file.root)?;
+
+    match file.compression {
+        Compression::None => Ok(raw),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&raw).map_err(|e| e.to_string())?;
+            encoder.finish().map_err(|e| e.to_string())
+        }
+        Compression::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&raw).map_err(|e| e.to_string())?;
+            encoder.finish().map_err(|e| e.to_string())
+        }
+    }
+}
+
+pub fn read_file(path: &Path) -> Result<NbtFile, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    from_bytes(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Writes through a temporary file so a crash never leaves a half-written world file behind.
+pub fn write_file(path: &Path, file: &NbtFile) -> Result<(), String> {
+    let bytes = to_bytes(file)?;
+    let tmp = path.with_extension("cubely-tmp");
+
+    fs::write(&tmp, bytes).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| {
+        fs::remove_file(&tmp).ok();
+        e.to_string()
+    })
+}
+
+// Every tag type, awkward strings and an empty list, shared by the tests of each format
+#[cfg(test)]
+pub(crate) fn test_sample() -> Tag {
+    Tag::Compound(vec![
+        ("byte".into(), Tag::Byte(-3)),
+        ("short".into(), Tag::Short(300)),
+        ("int".into(), Tag::Int(-70000)),
+        ("long".into(), Tag::Long(-4611686018427387904)),
+        ("float".into(), Tag::Float(3.0)),
+        ("double".into(), Tag::Double(-0.1)),
+        ("bytes".into(), Tag::ByteArray(vec![1, -2, 3])),
+        (
+            "string".into(),
+            Tag::String("h\u{e9}llo \"q\" \0 \u{1F600}\n".into()),
+        ),
+        (
+            "empty".into(),
+            Tag::List {
+                element: TAG_END,
+                items: vec![],
+            },
+        ),
+        (
+            "items".into(),
+            Tag::List {
+                element: TAG_COMPOUND,
+                items: vec![Tag::Compound(vec![(
+                    "id".into(),
+                    Tag::String("minecraft:stone".into()),
+                )])],
+            },
+        ),
+        ("key with: \u{1F600}".into(), Tag::IntArray(vec![1, 2, -3])),
+        ("longs".into(), Tag::LongArray(vec![i64::MAX, i64::MIN])),
+        ("nested".into(), Tag::Compound(vec![])),
+    ])
+}
@@ -0,0 +1,436 @@
+use std::{
+    fs,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+
+use super::{binary, Tag};
+
+// Anvil region files (`r.<x>.<z>.mca`) hold 32x32 chunks.
+//
+// The file starts with two 4 KiB tables of 1024 entries each: chunk locations
+// (3 byte sector offset + 1 byte sector count) and last save timestamps. Each
+// chunk is `[u32 BE length][u8 compression][data]`, padded to whole sectors.
+// Chunks too large for the region file live in `c.<x>.<z>.mcc` next to it.
+
+pub const SECTOR_SIZE: u64 = 4096;
+pub const REGION_CHUNKS: i32 = 32;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkLocation {
+    // Chunk coordinates inside the region, 0..32
+    pub local_x: i32,
+    pub local_z: i32,
+    pub sector_offset: u32,
+    pub sector_count: u8,
+    // Unix seconds of the last time the game saved this chunk
+    pub timestamp: u32,
+}
+
+impl ChunkLocation {
+    pub fn is_present(&self) -> bool {
+        self.sector_offset >= 2 && self.sector_count > 0
+    }
+
+    pub fn index(&self) -> usize {
+        (self.local_x + self.local_z * REGION_CHUNKS) as usize
+    }
+}
+
+/// Region coordinates from a file name like `r.-1.3.mca`.
+pub fn parse_region_name(name: &str) -> Option<(i32, i32)> {
+    let mut parts = name.strip_prefix("r.")?.strip_suffix(".mca")?.split('.');
+    let x = parts.next()?.parse().ok()?;
+    let z = parts.next()?.parse().ok()?;
+
+    parts.next().is_none().then_some((x, z))
+}
+
+/// Every `r.<x>.<z>.mca` file in a region folder, with its coordinates.
+pub fn list_region_files(dir: &Path) -> Vec<(i32, i32, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let (x, z) = parse_region_name(name.to_str()?)?;
+            Some((x, z, entry.path()))
+        })
+        .collect()
+}
+
+/// Reads the location and timestamp tables. Returns only the chunks that exist.
+///
+/// Empty or truncated files (the game creates them before the first save) have no chunks.
+pub fn read_header(file: &mut fs::File) -> Result<Vec<ChunkLocation>, String> {
+    let mut header = vec![0u8; 2 * SECTOR_SIZE as usize];
+
+    file.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+    if file.read_exact(&mut header).is_err() {
+        return Ok(Vec::new());
+    }
+
+    let u32_at =
+        |i: usize| u32::from_be_bytes([header[i], header[i + 1], header[i + 2], header[i + 3]]);
+
+    Ok((0..1024)
+        .map(|i| {
+            let location = u32_at(i * 4);
+            ChunkLocation {
+                local_x: i as i32 % REGION_CHUNKS,
+                local_z: i as i32 / REGION_CHUNKS,
+                sector_offset: location >> 8,
+                sector_count: (location & 0xff) as u8,
+                timestamp: u32_at(SECTOR_SIZE as usize + i * 4),
+            }
+        })
+        .filter(ChunkLocation::is_present)
+        .collect())
+}
+
+/// Reads the raw payload of a chunk: its compression type and the still compressed data.
+pub fn read_chunk_payload(
+    file: &mut fs::File,
+    region_path: &Path,
+    region: (i32, i32),
+    location: &ChunkLocation,
+) -> Result<(u8, Vec<u8>), String> {
+    file.seek(SeekFrom::Start(location.sector_offset as u64 * SECTOR_SIZE))
+        .map_err(|e| e.to_string())?;
+
+    let mut head = [0u8; 5];
+    file.read_exact(&mut head)
+        .map_err(|_| "Chunk points past the end of the region file".to_string())?;
+
+    let length = u32::from_be_bytes([head[0], head[1], head[2], head[3]]) as u64;
+    let compression = head[4];
+
+    // The high bit means the data is stored in an external .mcc file
+    if compression & 0x80 != 0 {
+        let mcc = region_path.with_file_name(format!(
+            "c.{}.{}.mcc",
+            region.0 * REGION_CHUNKS + location.local_x,
+            region.1 * REGION_CHUNKS + location.local_z
+        ));
+        let data =
+            fs::read(&mcc).map_err(|e| format!("Failed to read {}: {}", mcc.display(), e))?;
+        return Ok((compression & 0x7f, data));
+    }
+
+    if length == 0 || length > location.sector_count as u64 * SECTOR_SIZE {
+        return Err("Chunk has an invalid length".into());
+    }
+
+    let mut data = vec![0u8; length as usize - 1];
+    file.read_exact(&mut data)
+        .map_err(|_| "Chunk is cut off".to_string())?;
+
+    Ok((compression, data))
+}
+
+/// Decompresses a chunk payload and parses its NBT.
+pub fn decode_chunk(compression: u8, data: &[u8]) -> Result<Tag, String> {
+    let mut raw = Vec::new();
+
+    match compression {
+        1 => {
+            GzDecoder::new(data)
+                .read_to_end(&mut raw)
+                .map_err(|e| format!("Invalid gzip chunk: {}", e))?;
+        }
+        2 => {
+            ZlibDecoder::new(data)
+                .read_to_end(&mut raw)
+                .map_err(|e| format!("Invalid zlib chunk: {}", e))?;
+        }
+        3 => raw = data.to_vec(),
+        4 => raw = decode_lz4_blocks(data)?,
+        other => return Err(format!("Unsupported chunk compression {}", other)),
+    }
+
+    binary::read_named(&raw).map(|(_, tag)| tag)
+}
+
+// LZ4 chunks (1.20.5+ option) use the framing of lz4-java's LZ4BlockOutputStream:
+// "LZ4Block", a token byte, then LE compressed length, decompressed length and checksum.
+fn decode_lz4_blocks(mut data: &[u8]) -> Result<Vec<u8>, String> {
+    const HEADER: usize = 8 + 1 + 4 + 4 + 4;
+    let mut out = Vec::new();
+
+    loop {
+        if data.len() < HEADER || &data[..8] != b"LZ4Block" {
+            return Err("Invalid LZ4 chunk".into());
+        }
+
+        let le = |i: usize| {
+            u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]) as usize
+        };
+        let method = data[8] & 0xf0;
+        let compressed = le(9);
+        let decompressed = le(13);
+
+        if decompressed == 0 {
+            return Ok(out);
+        }
+
+        let block = data
+            .get(HEADER..HEADER + compressed)
+            .ok_or("Invalid LZ4 chunk")?;
+
+        match method {
+            0x10 => out.extend_from_slice(block),
+            0x20 => out.extend(
+                lz4_flex::block::decompress(block, decompressed)
+                    .map_err(|e| format!("Invalid LZ4 chunk: {}", e))?,
+            ),
+            _ => return Err("Invalid LZ4 chunk".into()),
+        }
+
+        data = &data[HEADER + compressed..];
+    }
+}
+
+/// Reads and parses one chunk.
+pub fn read_chunk(
+    file: &mut fs::File,
+    region_path: &Path,
+    region: (i32, i32),
+    location: &ChunkLocation,
+) -> Result<Tag, String> {
+    let (compression, data) = read_chunk_payload(file, region_path, region, location)?;
+    decode_chunk(compression, &data)
+}
+
+/// Rewrites a region file keeping only the chunks `keep` accepts, packed without gaps.
+///
+/// Kept chunks are copied byte for byte. External `.mcc` files of removed chunks are
+/// deleted, and so is the region file itself once no chunk is left. The new file is
+/// written next to the old one and renamed over it.
+///
+/// Returns the number of removed chunks.
+pub fn retain_chunks(
+    path: &Path,
+    region: (i32, i32),
+    keep: &dyn Fn(&ChunkLocation) -> bool,
+) -> Result<usize, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let locations = read_header(&mut file)?;
+    // Windows can't replace a file that is still open
+    drop(file);
+
+    let (kept, removed): (Vec<ChunkLocation>, Vec<ChunkLocation>) =
+        locations.into_iter().partition(|l| keep(l));
+
+    if removed.is_empty() {
+        return Ok(0);
+    }
+
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let sector = |offset: u32, count: u8| {
+        let start = (offset as u64 * SECTOR_SIZE) as usize;
+        let end = start + count as usize * SECTOR_SIZE as usize;
+        (start.min(bytes.len()), end.min(bytes.len()))
+    };
+
+    for location in &removed {
+        let (start, end) = sector(location.sector_offset, location.sector_count);
+        if end - start > 4 && bytes[start + 4] & 0x80 != 0 {
+            fs::remove_file(path.with_file_name(format!(
+                "c.{}.{}.mcc",
+                region.0 * REGION_CHUNKS + location.local_x,
+                region.1 * REGION_CHUNKS + location.local_z
+            )))
+            .ok();
+        }
+    }
+
+    if kept.is_empty() {
+        fs::remove_file(path).map_err(|e| e.to_string())?;
+        return Ok(removed.len());
+    }
+
+    let mut out = vec![0u8; 2 * SECTOR_SIZE as usize];
+    let mut next_sector: u32 = 2;
+
+    for location in &kept {
+        let (start, end) = sector(location.sector_offset, location.sector_count);
+
+        let index = location.index() * 4;
+        let entry = (next_sector << 8) | location.sector_count as u32;
+        out[index..index + 4].copy_from_slice(&entry.to_be_bytes());
+        out[SECTOR_SIZE as usize + index..SECTOR_SIZE as usize + index + 4]
+            .copy_from_slice(&location.timestamp.to_be_bytes());
+
+        out.extend_from_slice(&bytes[start..end]);
+        // Pad chunks that were cut short by the end of the old file
+        out.resize(
+            (next_sector as usize + location.sector_count as usize) * SECTOR_SIZE as usize,
+            0,
+        );
+        next_sector += location.sector_count as u32;
+    }
+
+    let tmp = path.with_extension("mca.cubely-tmp");
+    fs::write(&tmp, &out).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| {
+        fs::remove_file(&tmp).ok();
+        e.to_string()
+    })?;
+
+    Ok(removed.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::{to_bytes, Compression, NbtFile};
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("cubely-region-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn chunk(inhabited: i64) -> Tag {
+        Tag::Compound(vec![("InhabitedTime".into(), Tag::Long(inhabited))])
+    }
+
+    // A region file with each chunk at `(index, sector, timestamp)`, zlib compressed.
+    // `external` chunks only have their header in the region and the data in a .mcc file.
+    fn write_region(path: &Path, chunks: &[(usize, u32, u32, bool)]) -> Vec<Tag> {
+        let mut bytes = vec![0u8; 2 * SECTOR_SIZE as usize];
+        let mut tags = Vec::new();
+
+        for (i, &(index, sector, timestamp, external)) in chunks.iter().enumerate() {
+            let tag = chunk(i as i64 * 100);
+            let data = to_bytes(&NbtFile {
+                name: String::new(),
+                root: tag.clone(),
+                compression: Compression::Zlib,
+            })
+            .unwrap();
+
+            bytes[index * 4..index * 4 + 4].copy_from_slice(&((sector << 8) | 1).to_be_bytes());
+            let at = SECTOR_SIZE as usize + index * 4;
+            bytes[at..at + 4].copy_from_slice(&timestamp.to_be_bytes());
+
+            let start = sector as usize * SECTOR_SIZE as usize;
+            bytes.resize(start + SECTOR_SIZE as usize, 0);
+
+            if external {
+                bytes[start..start + 4].copy_from_slice(&1u32.to_be_bytes());
+                bytes[start + 4] = 0x80 | 2;
+                let (x, z) = (index as i32 % REGION_CHUNKS, index as i32 / REGION_CHUNKS);
+                fs::write(path.with_file_name(format!("c.{}.{}.mcc", x, z)), &data).unwrap();
+            } else {
+                bytes[start..start + 4].copy_from_slice(&(data.len() as u32 + 1).to_be_bytes());
+                bytes[start + 4] = 2;
+                bytes[start + 5..start + 5 + data.len()].copy_from_slice(&data);
+            }
+
+            tags.push(tag);
+        }
+
+        fs::write(path, bytes).unwrap();
+        tags
+    }
+
+    fn read_all(path: &Path) -> Vec<(ChunkLocation, Tag)> {
+        let mut file = fs::File::open(path).unwrap();
+        let locations = read_header(&mut file).unwrap();
+
+        locations
+            .into_iter()
+            .map(|l| (l, read_chunk(&mut file, path, (0, 0), &l).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn region_names() {
+        assert_eq!(parse_region_name("r.-1.2.mca"), Some((-1, 2)));
+        assert_eq!(parse_region_name("r.0.0.mcr"), None);
+        assert_eq!(parse_region_name("r.0.0.1.mca"), None);
+    }
+
+    #[test]
+    fn reads_chunks() {
+        let dir = scratch("read");
+        let path = dir.join("r.0.0.mca");
+        let tags = write_region(&path, &[(2 + 3 * 32, 2, 77, false), (40, 3, 78, true)]);
+
+        let chunks = read_all(&path);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].0.local_x, chunks[0].0.local_z), (8, 1));
+        assert_eq!(chunks[0].1, tags[1]);
+        assert_eq!((chunks[1].0.local_x, chunks[1].0.local_z), (2, 3));
+        assert_eq!(chunks[1].0.timestamp, 77);
+        assert_eq!(chunks[1].1, tags[0]);
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn decodes_lz4_chunks() {
+        let tag = chunk(42);
+        let raw = binary::write_named("", &tag).unwrap();
+        let compressed = lz4_flex::block::compress(&raw);
+
+        // One compressed block followed by the empty end block
+        let mut data = b"LZ4Block".to_vec();
+        data.push(0x20 | 6);
+        data.extend((compressed.len() as u32).to_le_bytes());
+        data.extend((raw.len() as u32).to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.extend(&compressed);
+        data.extend(b"LZ4Block");
+        data.push(0x10);
+        data.extend([0u8; 12]);
+
+        assert_eq!(decode_chunk(4, &data).unwrap(), tag);
+        assert!(decode_chunk(4, &data[..10]).is_err());
+    }
+
+    #[test]
+    fn retain_chunks_round_trip() {
+        let dir = scratch("retain");
+        let path = dir.join("r.0.0.mca");
+        // Gaps between the chunks, which the rewrite packs together
+        let tags = write_region(
+            &path,
+            &[(5, 2, 10, false), (40, 5, 11, true), (1000, 9, 12, false)],
+        );
+        let mcc = dir.join("c.8.1.mcc");
+        assert!(mcc.exists());
+
+        let removed = retain_chunks(&path, (0, 0), &|l| l.index() != 40).unwrap();
+        assert_eq!(removed, 1);
+        assert!(!mcc.exists());
+        assert_eq!(fs::metadata(&path).unwrap().len(), 4 * SECTOR_SIZE);
+
+        let chunks = read_all(&path);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].0.index(), 5);
+        assert_eq!(chunks[0].0.sector_offset, 2);
+        assert_eq!(chunks[0].1, tags[0]);
+        assert_eq!(chunks[1].0.index(), 1000);
+        assert_eq!(chunks[1].0.sector_offset, 3);
+        assert_eq!(chunks[1].0.timestamp, 12);
+        assert_eq!(chunks[1].1, tags[2]);
+
+        // Keeping everything leaves the file alone, removing everything deletes it
+        let before = fs::read(&path).unwrap();
+        assert_eq!(retain_chunks(&path, (0, 0), &|_| true).unwrap(), 0);
+        assert_eq!(fs::read(&path).unwrap(), before);
+
+        assert_eq!(retain_chunks(&path, (0, 0), &|_| false).unwrap(), 2);
+        assert!(!path.exists());
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
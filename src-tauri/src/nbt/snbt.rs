@@ -0,0 +1,350 @@
+use super::*;
+
+// SNBT, the text form of NBT used by commands like `/data` and `/give`.
+// e.g. `{Name:"Steve",Pos:[1.5d,64.0d,-3.0d],Inventory:[{id:"minecraft:stone",Count:1b}]}`
+
+// Keys and strings that can be written without quotes
+fn is_bare(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'))
+}
+
+fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Floats need a decimal point or exponent to stay floats after a round trip
+fn float_text(value: f64) -> String {
+    let text = format!("{}", value);
+    if text.contains(['.', 'e', 'E', 'N', 'i']) {
+        text
+    } else {
+        format!("{}.0", text)
+    }
+}
+
+fn join<T>(items: &[T], f: impl Fn(&T) -> String) -> String {
+    items.iter().map(f).collect::<Vec<_>>().join(",")
+}
+
+pub fn to_snbt(tag: &Tag) -> String {
+    match tag {
+        Tag::Byte(v) => format!("{}b", v),
+        Tag::Short(v) => format!("{}s", v),
+        Tag::Int(v) => v.to_string(),
+        Tag::Long(v) => format!("{}L", v),
+        Tag::Float(v) => format!("{}f", float_text(*v as f64)),
+        Tag::Double(v) => format!("{}d", float_text(*v)),
+        Tag::ByteArray(values) => format!("[B;{}]", join(values, |v| format!("{}B", v))),
+        Tag::String(v) => quote(v),
+        Tag::List { items, .. } => format!("[{}]", join(items, to_snbt)),
+        Tag::Compound(entries) => format!(
+            "{{{}}}",
+            join(entries, |(k, v)| {
+                let key = if is_bare(k) { k.clone() } else { quote(k) };
+                format!("{}:{}", key, to_snbt(v))
+            })
+        ),
+        Tag::IntArray(values) => format!("[I;{}]", join(values, |v| v.to_string())),
+        Tag::LongArray(values) => format!("[L;{}]", join(values, |v| format!("{}L", v))),
+    }
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    source: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> String {
+        format!(
+            "{} at position {} in SNBT `{}`",
+            message, self.pos, self.source
+        )
+    }
+
+    fn skip_ws(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_ws();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected `{}`", c)))
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, String> {
+        let quote = self.chars[self.pos];
+        self.pos += 1;
+        let mut out = String::new();
+
+        loop {
+            let c = *self
+                .chars
+                .get(self.pos)
+                .ok_or_else(|| self.error("Unterminated string"))?;
+            self.pos += 1;
+
+            match c {
+                '\\' => {
+                    let escaped = *self
+                        .chars
+                        .get(self.pos)
+                        .ok_or_else(|| self.error("Unterminated string"))?;
+                    self.pos += 1;
+                    out.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        other => other,
+                    });
+                }
+                c if c == quote => return Ok(out),
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn bare(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .chars
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn key(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some('"') | Some('\'') => self.quoted(),
+            _ => {
+                let key = self.bare();
+                if key.is_empty() {
+                    Err(self.error("Expected a key"))
+                } else {
+                    Ok(key)
+                }
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Tag, String> {
+        match self.peek() {
+            Some('{') => self.compound(),
+            Some('[') => self.list_or_array(),
+            Some('"') | Some('\'') => Ok(Tag::String(self.quoted()?)),
+            Some(_) => {
+                let text = self.bare();
+                if text.is_empty() {
+                    return Err(self.error("Expected a value"));
+                }
+                Ok(parse_bare_value(&text))
+            }
+            None => Err(self.error("Unexpected end")),
+        }
+    }
+
+    fn compound(&mut self) -> Result<Tag, String> {
+        self.expect('{')?;
+        let mut entries: Vec<(String, Tag)> = Vec::new();
+
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Tag::Compound(entries));
+        }
+
+        loop {
+            let key = self.key()?;
+            self.expect(':')?;
+            let value = self.value()?;
+
+            match entries.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, existing)) => *existing = value,
+                None => entries.push((key, value)),
+            }
+
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(Tag::Compound(entries));
+                }
+                _ => return Err(self.error("Expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn list_or_array(&mut self) -> Result<Tag, String> {
+        self.expect('[')?;
+
+        // Typed arrays: [B;...] [I;...] [L;...]
+        if let (Some(kind), Some(';')) = (
+            self.chars.get(self.pos).copied(),
+            self.chars.get(self.pos + 1).copied(),
+        ) {
+            if matches!(kind, 'B' | 'I' | 'L') {
+                self.pos += 2;
+                let items = self.items()?;
+                let numbers = items
+                    .iter()
+                    .map(|t| match t {
+                        Tag::Byte(_) | Tag::Short(_) | Tag::Int(_) | Tag::Long(_) => t.as_i64(),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<i64>>>()
+                    .ok_or_else(|| self.error("Arrays can only contain integers"))?;
+
+                return Ok(match kind {
+                    'B' => Tag::ByteArray(numbers.iter().map(|v| *v as i8).collect()),
+                    'I' => Tag::IntArray(numbers.iter().map(|v| *v as i32).collect()),
+                    _ => Tag::LongArray(numbers),
+                });
+            }
+        }
+
+        let items = self.items()?;
+        let element = items.first().map(|t| t.id()).unwrap_or(TAG_END);
+
+        if items.iter().any(|t| t.id() != element) {
+            return Err(self.error("All elements of a list must have the same type"));
+        }
+
+        Ok(Tag::List { element, items })
+    }
+
+    // Comma separated values up to the closing `]`
+    fn items(&mut self) -> Result<Vec<Tag>, String> {
+        let mut items = Vec::new();
+
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(items);
+        }
+
+        loop {
+            items.push(self.value()?);
+
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(items);
+                }
+                _ => return Err(self.error("Expected `,` or `]`")),
+            }
+        }
+    }
+}
+
+// Unquoted values are numbers when they parse as one, booleans become bytes, anything else is a string
+fn parse_bare_value(text: &str) -> Tag {
+    let lower = text.to_ascii_lowercase();
+
+    match lower.as_str() {
+        "true" => return Tag::Byte(1),
+        "false" => return Tag::Byte(0),
+        _ => {}
+    }
+
+    let (body, suffix) = match lower.char_indices().last() {
+        Some((i, c)) if matches!(c, 'b' | 's' | 'l' | 'f' | 'd') => (&text[..i], Some(c)),
+        _ => (text, None),
+    };
+
+    let parsed = match suffix {
+        Some('b') => body.parse().ok().map(Tag::Byte),
+        Some('s') => body.parse().ok().map(Tag::Short),
+        Some('l') => body.parse().ok().map(Tag::Long),
+        Some('f') => body.parse().ok().map(Tag::Float),
+        Some('d') => body.parse().ok().map(Tag::Double),
+        _ => text.parse().ok().map(Tag::Int).or_else(|| {
+            // A decimal point without suffix is a double
+            text.contains(['.', 'e', 'E'])
+                .then(|| text.parse().ok().map(Tag::Double))
+                .flatten()
+        }),
+    };
+
+    parsed.unwrap_or_else(|| Tag::String(text.to_string()))
+}
+
+pub fn parse_snbt(source: &str) -> Result<Tag, String> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        pos: 0,
+        source,
+    };
+
+    let tag = parser.value()?;
+
+    if parser.peek().is_some() {
+        return Err(parser.error("Unexpected trailing characters"));
+    }
+
+    Ok(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let sample = test_sample();
+        let text = to_snbt(&sample);
+
+        assert_eq!(parse_snbt(&text).unwrap(), sample);
+        assert_eq!(to_snbt(&parse_snbt(&text).unwrap()), text);
+    }
+
+    #[test]
+    fn parses_what_players_type() {
+        let tag = parse_snbt(
+            "{a: 1b, b: [I; 1, 2], c: 'x\\'y', d: true, e: 1.5, f: [1L, 2L], g: abc, h: \"\u{1F600}\"}",
+        )
+        .unwrap();
+
+        assert_eq!(tag.get("a"), Some(&Tag::Byte(1)));
+        assert_eq!(tag.get("b"), Some(&Tag::IntArray(vec![1, 2])));
+        assert_eq!(tag.get("c"), Some(&Tag::String("x'y".into())));
+        assert_eq!(tag.get("d"), Some(&Tag::Byte(1)));
+        assert_eq!(tag.get("e"), Some(&Tag::Double(1.5)));
+        assert_eq!(tag.get("g"), Some(&Tag::String("abc".into())));
+        assert_eq!(tag.get("h"), Some(&Tag::String("\u{1F600}".into())));
+    }
+
+    #[test]
+    fn rejects_mixed_lists() {
+        assert!(parse_snbt("[1b, \"two\"]").is_err());
+        assert!(parse_snbt("{a: 1").is_err());
+    }
+}
@@ -1,11 +1,15 @@
-use crate::commands::{server_management::ActiveServer, versions_loaders::LoaderSupportCache};
+use crate::commands::{
+    resource_packs::PackHost, server_management::ActiveServer,
+    versions_loaders::LoaderSupportCache,
+};
 use std::{
     path::PathBuf,
     sync::{Arc, Mutex},
 };
 use tauri::AppHandle;
 
-#[derive(Default)]
+// Cloning shares the same state, every field is an Arc
+#[derive(Default, Clone)]
 pub struct AppState {
     pub app_handle: Arc<Mutex<Option<AppHandle>>>,
     pub ping_count: Arc<Mutex<u32>>,
@@ -14,6 +18,7 @@ pub struct AppState {
     pub java_base_dir: Arc<Mutex<Option<PathBuf>>>,
     pub ngrok_base_dir: Arc<Mutex<Option<PathBuf>>>,
     pub playit_base_dir: Arc<Mutex<Option<PathBuf>>>,
+    pub pack_host: Arc<Mutex<Option<PackHost>>>,
 }
 
 impl AppState {
@@ -0,0 +1,190 @@
+use std::{
+    fs,
+    io::{Read, Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+/// Recursively adds the contents of `src` to an open zip under `prefix`. [Ex: world/ -> world/region/r.0.0.mca]
+///
+/// `skip` receives every path relative to `src` (using `/` separators) and can
+/// return `true` to leave that file or folder (and everything below it) out.
+///
+/// Pass an empty `prefix` to place the contents at the root of the zip.
+pub fn add_dir_to_zip<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    src: &Path,
+    prefix: &str,
+    skip: &dyn Fn(&str) -> bool,
+) -> Result<(), String> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut stack = vec![src.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let path = entry.path();
+
+            let rel = path
+                .strip_prefix(src)
+                .map_err(|e| e.to_string())?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if skip(&rel) {
+                continue;
+            }
+
+            let name = if prefix.is_empty() {
+                rel
+            } else {
+                format!("{}/{}", prefix.trim_end_matches('/'), rel)
+            };
+
+            if path.is_dir() {
+                zip.add_directory(format!("{}/", name), options)
+                    .map_err(|e| e.to_string())?;
+                stack.push(path);
+            } else {
+                // session.lock is held open by a running server on Windows, it is never needed in an archive
+                let mut file = match fs::File::open(&path) {
+                    Ok(f) => f,
+                    Err(_) if path.ends_with("session.lock") => continue,
+                    Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+                };
+
+                zip.start_file(name, options).map_err(|e| e.to_string())?;
+                std::io::copy(&mut file, zip).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Zips the whole `src` directory into a new file at `dest`.
+///
+/// See `add_dir_to_zip` for how `skip` works. An existing file at `dest` is overwritten.
+pub fn zip_dir(src: &Path, dest: &Path, skip: &dyn Fn(&str) -> bool) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let file = fs::File::create(dest).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+
+    add_dir_to_zip(&mut zip, src, "", skip)?;
+
+    zip.finish().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Extracts the entries of a zip into `dest`, letting the caller decide where each entry goes.
+///
+/// `map` receives the (already sanitized) relative path of every entry and returns
+/// the path it should be written to relative to `dest`, or `None` to skip it.
+/// Entries that try to escape the archive root (zip-slip) are always skipped.
+///
+/// Returns the number of files written.
+pub fn extract_zip_filtered<R: Read + Seek>(
+    reader: R,
+    dest: &Path,
+    map: &dyn Fn(&Path) -> Option<PathBuf>,
+) -> Result<usize, String> {
+    let mut archive = ZipArchive::new(reader).map_err(|e| e.to_string())?;
+    let mut written = 0;
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+
+        let Some(rel) = file.enclosed_name() else {
+            continue;
+        };
+
+        let Some(rel) = map(&rel) else {
+            continue;
+        };
+
+        let outpath = dest.join(rel);
+
+        if file.is_dir() {
+            fs::create_dir_all(&outpath).map_err(|e| e.to_string())?;
+        } else {
+            if let Some(p) = outpath.parent() {
+                fs::create_dir_all(p).map_err(|e| e.to_string())?;
+            }
+            let mut outfile = fs::File::create(&outpath).map_err(|e| e.to_string())?;
+            std::io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
+            written += 1;
+        }
+    }
+
+    Ok(written)
+}
+
+/// Extracts every entry of the zip at `src` into `dest`, keeping the archive's layout.
+pub fn extract_zip(src: &Path, dest: &Path) -> Result<usize, String> {
+    let file = fs::File::open(src).map_err(|e| e.to_string())?;
+    extract_zip_filtered(file, dest, &|rel| Some(rel.to_path_buf()))
+}
+
+/// Returns the total size in bytes of every file below `path`. Unreadable entries are ignored.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                dir_size(&path)
+            } else {
+                entry.metadata().map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+/// Recursively copies `src` into `dest` (created if missing). `skip` works like in `add_dir_to_zip`.
+pub fn copy_dir(src: &Path, dest: &Path, skip: &dyn Fn(&str) -> bool) -> Result<(), String> {
+    let mut stack = vec![src.to_path_buf()];
+
+    fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let path = entry.path();
+            let rel = path.strip_prefix(src).map_err(|e| e.to_string())?;
+
+            let rel_str = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if skip(&rel_str) {
+                continue;
+            }
+
+            let target = dest.join(rel);
+
+            if path.is_dir() {
+                fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+                stack.push(path);
+            } else {
+                fs::copy(&path, &target)
+                    .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+            }
+        }
+    }
+
+    Ok(())
+}
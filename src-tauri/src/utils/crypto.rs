@@ -0,0 +1,405 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::Path,
+};
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, KeyInit, OsRng, Payload},
+    Aes256Gcm, Nonce,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Serialize};
+
+// Passphrase based file encryption used for backups.
+//
+// Files are split into chunks that are each sealed with AES-256-GCM. The nonce of
+// every chunk is a random prefix plus the chunk counter, and the last chunk is
+// authenticated as such, so reordered, truncated or modified files are rejected.
+//
+// Layout: `MAGIC` followed by `[u32 BE length][ciphertext + tag]` per chunk.
+
+const MAGIC: &[u8; 8] = b"CUBELY\x00\x01";
+const CHUNK_SIZE: usize = 1024 * 1024;
+const TAG_SIZE: usize = 16;
+
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+// The sidecar is not authenticated, so its KDF settings are bounded before use: a crafted
+// file could otherwise ask Argon2 for terabytes of memory or years of iterations
+const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ITERATIONS: u32 = 16;
+const MAX_PARALLELISM: u32 = 16;
+const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Everything needed (besides the passphrase) to decrypt a file. Stored next to the backup.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EncryptionInfo {
+    pub cipher: String,       // "aes-256-gcm"
+    pub kdf: String,          // "argon2id"
+    pub salt: String,         // hex
+    pub nonce_prefix: String, // hex, 8 bytes
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub chunk_size: u32,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// The sidecar isn't trusted, so anything that isn't pairs of hex digits is an error
+fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let invalid = || "Invalid hex value in encryption info".to_string();
+
+    if hex.len() % 2 != 0 {
+        return Err(invalid());
+    }
+
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+            u8::from_str_radix(pair, 16).map_err(|_| invalid())
+        })
+        .collect()
+}
+
+fn derive_key(passphrase: &str, info: &EncryptionInfo) -> Result<Aes256Gcm, String> {
+    if info.cipher != "aes-256-gcm" || info.kdf != "argon2id" {
+        return Err(format!(
+            "Unsupported backup encryption ({} / {})",
+            info.cipher, info.kdf
+        ));
+    }
+
+    if info.memory_kib > MAX_MEMORY_KIB
+        || info.iterations > MAX_ITERATIONS
+        || info.parallelism > MAX_PARALLELISM
+        || !(1..=MAX_CHUNK_SIZE).contains(&info.chunk_size)
+    {
+        return Err("Backup encryption settings are out of range".into());
+    }
+
+    let params = Params::new(info.memory_kib, info.iterations, info.parallelism, Some(32))
+        .map_err(|e| e.to_string())?;
+
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &from_hex(&info.salt)?, &mut key)
+        .map_err(|e| e.to_string())?;
+
+    Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())
+}
+
+fn chunk_nonce(prefix: &[u8], counter: u32) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..8].copy_from_slice(prefix);
+    nonce[8..].copy_from_slice(&counter.to_be_bytes());
+    nonce
+}
+
+// The last chunk is bound to its position so cutting the file short is detected
+fn chunk_aad(last: bool) -> &'static [u8] {
+    if last {
+        b"last"
+    } else {
+        b"more"
+    }
+}
+
+// Fills `buf` as far as possible, returning how many bytes were read (0 at EOF)
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize, String> {
+    let mut filled = 0;
+
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]).map_err(|e| e.to_string())? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+
+    Ok(filled)
+}
+
+/// Whether a file starts like an encrypted backup, independent of what its sidecar says.
+pub fn is_encrypted_file(path: &Path) -> Result<bool, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut magic = [0u8; 8];
+
+    Ok(read_full(&mut file, &mut magic)? == MAGIC.len() && &magic == MAGIC)
+}
+
+/// Encrypts `src` into `dest` with a key derived from `passphrase`.
+pub fn encrypt_file(src: &Path, dest: &Path, passphrase: &str) -> Result<EncryptionInfo, String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+
+    let mut salt = [0u8; 16];
+    let mut nonce_prefix = [0u8; 8];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce_prefix);
+
+    let info = EncryptionInfo {
+        cipher: "aes-256-gcm".into(),
+        kdf: "argon2id".into(),
+        salt: to_hex(&salt),
+        nonce_prefix: to_hex(&nonce_prefix),
+        memory_kib: 64 * 1024,
+        iterations: 3,
+        parallelism: 1,
+        chunk_size: CHUNK_SIZE as u32,
+    };
+
+    let cipher = derive_key(passphrase, &info)?;
+
+    let mut input = fs::File::open(src).map_err(|e| e.to_string())?;
+    let mut output = std::io::BufWriter::new(fs::File::create(dest).map_err(|e| e.to_string())?);
+
+    output.write_all(MAGIC).map_err(|e| e.to_string())?;
+
+    // Read one chunk ahead so we know which chunk is the last one
+    let mut current = vec![0u8; CHUNK_SIZE];
+    let mut next = vec![0u8; CHUNK_SIZE];
+    let mut current_len = read_full(&mut input, &mut current)?;
+    let mut counter: u32 = 0;
+
+    loop {
+        let next_len = if current_len == CHUNK_SIZE {
+            read_full(&mut input, &mut next)?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+
+        let sealed = cipher
+            .encrypt(
+                Nonce::from_slice(&chunk_nonce(&nonce_prefix, counter)),
+                Payload {
+                    msg: &current[..current_len],
+                    aad: chunk_aad(last),
+                },
+            )
+            .map_err(|_| "Failed to encrypt backup".to_string())?;
+
+        output
+            .write_all(&(sealed.len() as u32).to_be_bytes())
+            .and_then(|_| output.write_all(&sealed))
+            .map_err(|e| e.to_string())?;
+
+        if last {
+            break;
+        }
+
+        std::mem::swap(&mut current, &mut next);
+        current_len = next_len;
+        counter = counter
+            .checked_add(1)
+            .ok_or("Backup is too large to encrypt")?;
+    }
+
+    output.flush().map_err(|e| e.to_string())?;
+
+    Ok(info)
+}
+
+/// Decrypts `src` into `dest`, failing on a wrong passphrase or any tampering.
+///
+/// `dest` may contain partial output when this fails; callers must discard it.
+pub fn decrypt_file(
+    src: &Path,
+    dest: &Path,
+    passphrase: &str,
+    info: &EncryptionInfo,
+) -> Result<(), String> {
+    const TAMPERED: &str = "Wrong passphrase, or the backup has been modified or corrupted";
+
+    let cipher = derive_key(passphrase, info)?;
+    let nonce_prefix = from_hex(&info.nonce_prefix)?;
+
+    if nonce_prefix.len() != 8 {
+        return Err("Invalid nonce in encryption info".into());
+    }
+
+    let mut input = std::io::BufReader::new(fs::File::open(src).map_err(|e| e.to_string())?);
+    let mut output = std::io::BufWriter::new(fs::File::create(dest).map_err(|e| e.to_string())?);
+
+    let mut magic = [0u8; 8];
+    if read_full(&mut input, &mut magic)? != MAGIC.len() || &magic != MAGIC {
+        return Err("Not an encrypted Cubely backup".into());
+    }
+
+    let max_sealed = info.chunk_size as usize + TAG_SIZE;
+    let mut counter: u32 = 0;
+
+    loop {
+        let mut len = [0u8; 4];
+        if read_full(&mut input, &mut len)? != 4 {
+            // Ran out of data before the chunk marked as last
+            return Err(TAMPERED.into());
+        }
+
+        let len = u32::from_be_bytes(len) as usize;
+        if !(TAG_SIZE..=max_sealed).contains(&len) {
+            return Err(TAMPERED.into());
+        }
+
+        let mut sealed = vec![0u8; len];
+        if read_full(&mut input, &mut sealed)? != len {
+            return Err(TAMPERED.into());
+        }
+
+        let nonce = chunk_nonce(&nonce_prefix, counter);
+
+        // Try as a middle chunk first, then as the final one
+        let (plain, last) = match cipher.decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &sealed,
+                aad: chunk_aad(false),
+            },
+        ) {
+            Ok(plain) => (plain, false),
+            Err(_) => {
+                let plain = cipher
+                    .decrypt(
+                        Nonce::from_slice(&nonce),
+                        Payload {
+                            msg: &sealed,
+                            aad: chunk_aad(true),
+                        },
+                    )
+                    .map_err(|_| TAMPERED.to_string())?;
+                (plain, true)
+            }
+        };
+
+        output.write_all(&plain).map_err(|e| e.to_string())?;
+
+        if last {
+            // Nothing may follow the final chunk
+            let mut extra = [0u8; 1];
+            if read_full(&mut input, &mut extra)? != 0 {
+                return Err(TAMPERED.into());
+            }
+            break;
+        }
+
+        counter = counter.checked_add(1).ok_or(TAMPERED)?;
+    }
+
+    output.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery";
+
+    // A fresh folder per test so they can run in parallel
+    fn scratch(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("cubely-crypto-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // Two and a bit chunks, so first, middle and last chunks are all covered
+    fn encrypted(dir: &Path) -> (Vec<u8>, EncryptionInfo) {
+        let data: Vec<u8> = (0..CHUNK_SIZE * 2 + 7).map(|i| (i % 251) as u8).collect();
+        fs::write(dir.join("plain"), &data).unwrap();
+
+        let info = encrypt_file(&dir.join("plain"), &dir.join("sealed"), PASSPHRASE).unwrap();
+        (data, info)
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = scratch("round-trip");
+        let (data, info) = encrypted(&dir);
+
+        assert!(is_encrypted_file(&dir.join("sealed")).unwrap());
+        assert!(!is_encrypted_file(&dir.join("plain")).unwrap());
+
+        decrypt_file(&dir.join("sealed"), &dir.join("out"), PASSPHRASE, &info).unwrap();
+        assert_eq!(fs::read(dir.join("out")).unwrap(), data);
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let dir = scratch("wrong-passphrase");
+        let (_, info) = encrypted(&dir);
+
+        let result = decrypt_file(
+            &dir.join("sealed"),
+            &dir.join("out"),
+            "horse battery staple",
+            &info,
+        );
+        assert!(result.is_err());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn flipped_byte_is_rejected() {
+        let dir = scratch("flipped-byte");
+        let (_, info) = encrypted(&dir);
+        let sealed = fs::read(dir.join("sealed")).unwrap();
+
+        // One byte in each chunk's ciphertext, and one in the length of the last chunk
+        let last_len_at = MAGIC.len() + 2 * (4 + CHUNK_SIZE + TAG_SIZE);
+        for at in [
+            MAGIC.len() + 4,
+            sealed.len() / 2,
+            sealed.len() - 1,
+            last_len_at,
+        ] {
+            let mut tampered = sealed.clone();
+            tampered[at] ^= 0x01;
+            fs::write(dir.join("tampered"), &tampered).unwrap();
+
+            let result = decrypt_file(&dir.join("tampered"), &dir.join("out"), PASSPHRASE, &info);
+            assert!(result.is_err(), "flipping byte {} was not detected", at);
+        }
+
+        // Dropping the last chunk must be caught too
+        fs::write(dir.join("tampered"), &sealed[..last_len_at]).unwrap();
+        assert!(decrypt_file(&dir.join("tampered"), &dir.join("out"), PASSPHRASE, &info).is_err());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn oversized_kdf_settings_are_rejected() {
+        let dir = scratch("kdf-bounds");
+        let (_, info) = encrypted(&dir);
+
+        let crafted = EncryptionInfo {
+            memory_kib: u32::MAX,
+            ..info
+        };
+        let result = decrypt_file(&dir.join("sealed"), &dir.join("out"), PASSPHRASE, &crafted);
+        assert!(result.is_err());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn non_ascii_hex_is_rejected() {
+        assert_eq!(from_hex("00ff10").unwrap(), vec![0x00, 0xff, 0x10]);
+        assert!(from_hex("0").is_err());
+        assert!(from_hex("zz").is_err());
+        // Byte slicing this would split the two-byte é
+        assert!(from_hex("aé0").is_err());
+        assert!(from_hex("éé").is_err());
+    }
+}
@@ -1 +1,7 @@
+pub mod archive;
+pub mod crypto;
+pub mod motd;
+pub mod pack_meta;
 pub mod path;
+pub mod properties;
+pub mod settings;
@@ -0,0 +1,458 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Parsing and writing of MOTDs, as legacy § codes or JSON text components.
+
+// Lines the multiplayer list shows, anything after is cut
+pub const MOTD_MAX_LINES: usize = 2;
+// Roughly what fits on one line of the multiplayer list
+pub const MOTD_LINE_WIDTH: usize = 59;
+
+// (code, name, hex)
+pub const COLORS: [(char, &str, &str); 16] = [
+    ('0', "black", "#000000"),
+    ('1', "dark_blue", "#0000AA"),
+    ('2', "dark_green", "#00AA00"),
+    ('3', "dark_aqua", "#00AAAA"),
+    ('4', "dark_red", "#AA0000"),
+    ('5', "dark_purple", "#AA00AA"),
+    ('6', "gold", "#FFAA00"),
+    ('7', "gray", "#AAAAAA"),
+    ('8', "dark_gray", "#555555"),
+    ('9', "blue", "#5555FF"),
+    ('a', "green", "#55FF55"),
+    ('b', "aqua", "#55FFFF"),
+    ('c', "red", "#FF5555"),
+    ('d', "light_purple", "#FF55FF"),
+    ('e', "yellow", "#FFFF55"),
+    ('f', "white", "#FFFFFF"),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct MotdSpan {
+    pub text: String,
+    pub color: Option<String>, // "#RRGGBB", None is the list's default gray
+    pub bold: bool,
+    pub italic: bool,
+    pub underlined: bool,
+    pub strikethrough: bool,
+    pub obfuscated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Motd {
+    pub spans: Vec<MotdSpan>,
+    pub plain: String,
+    pub is_json: bool,
+    pub warnings: Vec<String>,
+}
+
+impl MotdSpan {
+    fn same_style(&self, other: &MotdSpan) -> bool {
+        MotdSpan {
+            text: String::new(),
+            ..self.clone()
+        } == MotdSpan {
+            text: String::new(),
+            ..other.clone()
+        }
+    }
+}
+
+fn color_hex(name: &str) -> Option<String> {
+    if name.len() == 7 && name.starts_with('#') && name[1..].chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Some(name.to_uppercase());
+    }
+
+    COLORS
+        .iter()
+        .find(|(_, n, _)| *n == name)
+        .map(|(_, _, hex)| hex.to_string())
+}
+
+// Pushes text with a style, merging it into the last span when the style is the same
+fn push_span(spans: &mut Vec<MotdSpan>, text: &str, style: &MotdSpan) {
+    if text.is_empty() {
+        return;
+    }
+
+    match spans.last_mut() {
+        Some(last) if last.same_style(style) => last.text.push_str(text),
+        _ => spans.push(MotdSpan {
+            text: text.to_string(),
+            ..style.clone()
+        }),
+    }
+}
+
+// Applies § codes inside `text`, starting from `base`
+fn parse_legacy_into(
+    text: &str,
+    base: &MotdSpan,
+    spans: &mut Vec<MotdSpan>,
+    warnings: &mut Vec<String>,
+) {
+    // The § escape people paste from properties files or websites
+    let text = text.replace("\\u00a7", "§").replace("\\u00A7", "§");
+
+    let mut style = base.clone();
+    let mut buffer = String::new();
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '§' {
+            buffer.push(c);
+            continue;
+        }
+
+        push_span(spans, &buffer, &style);
+        buffer.clear();
+
+        let Some(code) = chars.next().map(|c| c.to_ascii_lowercase()) else {
+            warnings.push("The MOTD ends with a § that has no code after it".into());
+            break;
+        };
+
+        if let Some((_, _, hex)) = COLORS.iter().find(|(c, _, _)| *c == code) {
+            // A color also clears bold, italic...
+            style = MotdSpan {
+                color: Some(hex.to_string()),
+                ..Default::default()
+            };
+            continue;
+        }
+
+        match code {
+            'k' => style.obfuscated = true,
+            'l' => style.bold = true,
+            'm' => style.strikethrough = true,
+            'n' => style.underlined = true,
+            'o' => style.italic = true,
+            'r' => style = base.clone(),
+            other => warnings.push(format!("§{} is not a formatting code", other)),
+        }
+    }
+
+    push_span(spans, &buffer, &style);
+}
+
+fn parse_component(
+    value: &Value,
+    parent: &MotdSpan,
+    spans: &mut Vec<MotdSpan>,
+    warnings: &mut Vec<String>,
+) {
+    match value {
+        Value::String(text) => parse_legacy_into(text, parent, spans, warnings),
+        Value::Array(parts) => {
+            // The first element is the parent of the others
+            let Some((first, rest)) = parts.split_first() else {
+                return;
+            };
+            parse_component(first, parent, spans, warnings);
+            let style = match first {
+                Value::Object(_) => component_style(first, parent, warnings),
+                _ => parent.clone(),
+            };
+            for part in rest {
+                parse_component(part, &style, spans, warnings);
+            }
+        }
+        Value::Object(map) => {
+            let style = component_style(value, parent, warnings);
+
+            let text = map
+                .get("text")
+                .and_then(Value::as_str)
+                .or_else(|| map.get("translate").and_then(Value::as_str))
+                .unwrap_or_default();
+            parse_legacy_into(text, &style, spans, warnings);
+
+            if let Some(Value::Array(extra)) = map.get("extra") {
+                for part in extra {
+                    parse_component(part, &style, spans, warnings);
+                }
+            }
+        }
+        Value::Number(n) => push_span(spans, &n.to_string(), parent),
+        Value::Bool(b) => push_span(spans, &b.to_string(), parent),
+        Value::Null => {}
+    }
+}
+
+fn component_style(value: &Value, parent: &MotdSpan, warnings: &mut Vec<String>) -> MotdSpan {
+    let mut style = parent.clone();
+
+    if let Some(color) = value.get("color").and_then(Value::as_str) {
+        match color_hex(color) {
+            Some(hex) => style.color = Some(hex),
+            None => warnings.push(format!("{} is not a color", color)),
+        }
+    }
+
+    let flag =
+        |key: &str, current: bool| value.get(key).and_then(Value::as_bool).unwrap_or(current);
+    style.bold = flag("bold", style.bold);
+    style.italic = flag("italic", style.italic);
+    style.underlined = flag("underlined", style.underlined);
+    style.strikethrough = flag("strikethrough", style.strikethrough);
+    style.obfuscated = flag("obfuscated", style.obfuscated);
+
+    style
+}
+
+/// Parses a MOTD the way the multiplayer list shows it. With `read_json`, text starting with
+/// `{`, `[` or `"` that is valid JSON is read as a text component, anything else as legacy
+/// § text. Servers that don't read JSON send it as written, so it is shown as text.
+pub fn parse_motd(motd: &str, read_json: bool) -> Motd {
+    let mut spans = Vec::new();
+    let mut warnings = Vec::new();
+
+    let trimmed = motd.trim_start();
+    let json = trimmed
+        .starts_with(['{', '[', '"'])
+        .then(|| serde_json::from_str::<Value>(trimmed).ok())
+        .flatten();
+
+    if json.is_some() && !read_json {
+        warnings.push(
+            "This server shows the MOTD as written, JSON isn't read. Use § codes instead".into(),
+        );
+    }
+
+    let json = json.filter(|_| read_json);
+
+    match &json {
+        Some(component) => {
+            parse_component(component, &MotdSpan::default(), &mut spans, &mut warnings)
+        }
+        None => parse_legacy_into(motd, &MotdSpan::default(), &mut spans, &mut warnings),
+    }
+
+    let plain: String = spans.iter().map(|s| s.text.as_str()).collect();
+    let lines: Vec<&str> = plain.split('\n').collect();
+
+    if lines.len() > MOTD_MAX_LINES {
+        warnings.push(format!(
+            "Only the first {} lines are shown, the MOTD has {}",
+            MOTD_MAX_LINES,
+            lines.len()
+        ));
+    }
+
+    for (i, line) in lines.iter().take(MOTD_MAX_LINES).enumerate() {
+        if line.chars().count() > MOTD_LINE_WIDTH {
+            warnings.push(format!(
+                "Line {} is {} characters long, about {} fit before it's cut off",
+                i + 1,
+                line.chars().count(),
+                MOTD_LINE_WIDTH
+            ));
+        }
+    }
+
+    Motd {
+        spans,
+        plain,
+        is_json: json.is_some(),
+        warnings,
+    }
+}
+
+// The palette code closest to a hex color, legacy text can't hold anything else
+fn nearest_code(hex: &str) -> char {
+    let rgb = |h: &str| {
+        let n = u32::from_str_radix(h.trim_start_matches('#'), 16).unwrap_or(0);
+        [
+            (n >> 16) as i32 & 0xff,
+            (n >> 8) as i32 & 0xff,
+            n as i32 & 0xff,
+        ]
+    };
+    let target = rgb(hex);
+
+    COLORS
+        .iter()
+        .min_by_key(|(_, _, h)| {
+            rgb(h)
+                .iter()
+                .zip(target)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<i32>()
+        })
+        .map(|(code, _, _)| *code)
+        .unwrap_or('7')
+}
+
+/// Writes spans back as legacy § text. Colors outside the 16 legacy ones are rounded.
+pub fn spans_to_legacy(spans: &[MotdSpan]) -> String {
+    let mut out = String::new();
+    let mut current = MotdSpan::default();
+
+    for span in spans {
+        if span.text.is_empty() {
+            continue;
+        }
+
+        if !span.same_style(&current) {
+            let formats = |s: &MotdSpan| {
+                [
+                    (s.obfuscated, 'k'),
+                    (s.bold, 'l'),
+                    (s.strikethrough, 'm'),
+                    (s.underlined, 'n'),
+                    (s.italic, 'o'),
+                ]
+            };
+
+            // Formats can only be turned off by a color or §r, otherwise new ones are added
+            let only_adds = span.color == current.color
+                && formats(&current)
+                    .iter()
+                    .zip(formats(span))
+                    .all(|((was, _), (now, _))| !was || now);
+
+            if !only_adds {
+                match &span.color {
+                    Some(hex) => out.push_str(&format!("§{}", nearest_code(hex))),
+                    None => out.push_str("§r"),
+                }
+                current = MotdSpan::default();
+            }
+
+            for ((was, _), (now, code)) in formats(&current).iter().zip(formats(span)) {
+                if now && !was {
+                    out.push('§');
+                    out.push(code);
+                }
+            }
+
+            current = MotdSpan {
+                text: String::new(),
+                ..span.clone()
+            };
+        }
+
+        out.push_str(&span.text);
+    }
+
+    out
+}
+
+/// Writes spans as a JSON text component, keeping hex colors.
+pub fn spans_to_json(spans: &[MotdSpan]) -> String {
+    let extra: Vec<Value> = spans
+        .iter()
+        .filter(|s| !s.text.is_empty())
+        .map(|s| {
+            let mut part = serde_json::json!({ "text": s.text });
+
+            if let Some(color) = &s.color {
+                let name = COLORS
+                    .iter()
+                    .find(|(_, _, hex)| hex.eq_ignore_ascii_case(color))
+                    .map(|(_, name, _)| name.to_string())
+                    .unwrap_or(color.clone());
+                part["color"] = Value::String(name);
+            }
+
+            for (on, key) in [
+                (s.bold, "bold"),
+                (s.italic, "italic"),
+                (s.underlined, "underlined"),
+                (s.strikethrough, "strikethrough"),
+                (s.obfuscated, "obfuscated"),
+            ] {
+                if on {
+                    part[key] = Value::Bool(true);
+                }
+            }
+
+            part
+        })
+        .collect();
+
+    serde_json::json!({ "text": "", "extra": extra }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, color: Option<&str>) -> MotdSpan {
+        MotdSpan {
+            text: text.into(),
+            color: color.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn legacy_round_trip() {
+        let motd = parse_motd("§aHello §lWorld§r plain\n\\u00a7cred §o§mstruck", false);
+
+        assert_eq!(motd.plain, "Hello World plain\nred struck");
+        assert!(motd.warnings.is_empty());
+        assert_eq!(motd.spans[0], span("Hello ", Some("#55FF55")));
+        assert!(motd.spans[1].bold);
+
+        let legacy = spans_to_legacy(&motd.spans);
+        assert_eq!(legacy, "§aHello §lWorld§r plain\n§cred §m§ostruck");
+        assert_eq!(parse_motd(&legacy, false).spans, motd.spans);
+    }
+
+    #[test]
+    fn legacy_turns_formats_off_with_a_reset() {
+        let bold = MotdSpan {
+            bold: true,
+            ..span("a", Some("#FF5555"))
+        };
+        let spans = vec![bold, span("b", Some("#FF5555")), span("c", None)];
+
+        let legacy = spans_to_legacy(&spans);
+        assert_eq!(legacy, "§c§la§cb§rc");
+        assert_eq!(parse_motd(&legacy, false).spans, spans);
+
+        // Hex colors are rounded to the nearest legacy one
+        assert_eq!(spans_to_legacy(&[span("x", Some("#FFAB01"))]), "§6x");
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let motd = parse_motd(
+            r##"{"text":"A","color":"gold","extra":[{"text":"B","bold":true},{"text":"C","color":"#123456"}]}"##,
+            true,
+        );
+
+        assert!(motd.is_json);
+        assert_eq!(motd.plain, "ABC");
+        assert_eq!(motd.spans[0], span("A", Some("#FFAA00")));
+        assert!(motd.spans[1].bold);
+        assert_eq!(motd.spans[1].color.as_deref(), Some("#FFAA00"));
+        assert_eq!(motd.spans[2], span("C", Some("#123456")));
+
+        let json = spans_to_json(&motd.spans);
+        assert_eq!(parse_motd(&json, true).spans, motd.spans);
+    }
+
+    #[test]
+    fn json_is_text_for_servers_that_dont_read_it() {
+        let text = r#"{"text":"A","color":"gold"}"#;
+        let motd = parse_motd(text, false);
+
+        assert!(!motd.is_json);
+        assert_eq!(motd.plain, text);
+        assert_eq!(motd.warnings.len(), 1);
+    }
+
+    #[test]
+    fn warnings() {
+        assert!(!parse_motd("a\nb\nc", false).warnings.is_empty());
+        assert!(!parse_motd("x§", false).warnings.is_empty());
+        assert!(!parse_motd(&"x".repeat(MOTD_LINE_WIDTH + 1), false)
+            .warnings
+            .is_empty());
+    }
+}
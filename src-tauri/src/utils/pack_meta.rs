@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The `pack` section of a data or resource pack's pack.mcmeta.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PackMeta {
+    pub description: String,
+    pub pack_format: Option<i32>,
+    // Range declared with supported_formats (1.20.2+) or min_format / max_format (1.21.9+)
+    pub min_format: Option<i32>,
+    pub max_format: Option<i32>,
+}
+
+impl PackMeta {
+    pub fn supports(&self, format: i32) -> bool {
+        match (self.min_format, self.max_format) {
+            (Some(min), Some(max)) => (min..=max).contains(&format),
+            (Some(min), None) => format >= min,
+            (None, Some(max)) => format <= max,
+            (None, None) => self.pack_format == Some(format),
+        }
+    }
+}
+
+/// Flattens a JSON text component (string, object with text / extra, or array) to plain text.
+pub fn text_component_to_plain(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts.iter().map(text_component_to_plain).collect(),
+        Value::Object(map) => {
+            let mut text = map
+                .get("text")
+                .or_else(|| map.get("translate"))
+                .map(text_component_to_plain)
+                .unwrap_or_default();
+
+            if let Some(extra) = map.get("extra") {
+                text.push_str(&text_component_to_plain(extra));
+            }
+
+            text
+        }
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => String::new(),
+    }
+}
+
+// Formats are a number, or [major, minor] since 1.21.9 (only the major part matters here)
+fn format_number(value: &Value) -> Option<i32> {
+    match value {
+        Value::Array(parts) => parts.first().and_then(Value::as_i64),
+        other => other.as_i64(),
+    }
+    .map(|v| v as i32)
+}
+
+/// Parses the content of a pack.mcmeta file.
+pub fn parse_pack_meta(content: &str) -> Result<PackMeta, String> {
+    // Some tools write a BOM, the game accepts it
+    let json: Value = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("pack.mcmeta is not valid JSON: {}", e))?;
+
+    let pack = json
+        .get("pack")
+        .ok_or("pack.mcmeta has no \"pack\" section")?;
+
+    let (mut min_format, mut max_format) = match pack.get("supported_formats") {
+        Some(Value::Array(range)) => (
+            range.first().and_then(format_number),
+            range.get(1).and_then(format_number),
+        ),
+        Some(Value::Object(range)) => (
+            range.get("min_inclusive").and_then(format_number),
+            range.get("max_inclusive").and_then(format_number),
+        ),
+        Some(single) => (format_number(single), format_number(single)),
+        None => (None, None),
+    };
+
+    if let Some(min) = pack.get("min_format").and_then(format_number) {
+        min_format = Some(min);
+    }
+    if let Some(max) = pack.get("max_format").and_then(format_number) {
+        max_format = Some(max);
+    }
+
+    let meta = PackMeta {
+        description: pack
+            .get("description")
+            .map(text_component_to_plain)
+            .unwrap_or_default(),
+        pack_format: pack.get("pack_format").and_then(format_number),
+        min_format,
+        max_format,
+    };
+
+    if meta.pack_format.is_none() && meta.min_format.is_none() {
+        return Err("pack.mcmeta doesn't declare a pack format".into());
+    }
+
+    Ok(meta)
+}
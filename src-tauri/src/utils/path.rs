@@ -49,3 +49,105 @@ pub fn cleanup_empty_parent_dir(parent: &PathBuf) {
         }
     }
 }
+
+/// Returns the directory where all backups of a single server are stored.
+///
+/// Backups are kept next to (not inside) the servers directory so that they
+/// survive `delete_server` and never end up archived inside each other:
+/// - Windows: C:\Users\<you>\AppData\Roaming\Cubely\backups\<server_id>
+/// - macOS:   ~/Library/Application Support/Cubely/backups/<server_id>
+/// - Linux:   ~/.local/share/Cubely/backups/<server_id>
+///
+/// This function does NOT create the directory; it only resolves the path.
+pub fn backups_dir(server_id: &str) -> PathBuf {
+    let mut dir = dirs::data_dir().expect("Failed to get data dir");
+    dir.push("Cubely");
+    dir.push("backups");
+    dir.push(server_id);
+    dir
+}
+
+/// Returns the path of Cubely's app-wide settings file (backup destinations, credentials...).
+///
+/// Lives in the same `Cubely` data directory as `servers_dir()` and is never
+/// written into a server's `cubely.json`. The file may not exist yet.
+pub fn app_settings_path() -> PathBuf {
+    let mut path = dirs::data_dir().expect("Failed to get data dir");
+    path.push("Cubely");
+    path.push("settings.json");
+    path
+}
+
+/// Joins a path relative to a server directory, refusing anything that would
+/// escape it (absolute paths, `..`). [Ex: "world/playerdata/<uuid>.dat"]
+///
+/// Used for every command that takes a file path from the frontend.
+pub fn resolve_server_file(server_path: &str, relative: &str) -> Result<PathBuf, String> {
+    use std::path::Component;
+
+    let relative = std::path::Path::new(relative);
+
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("Invalid path: {}", relative.display()));
+    }
+
+    Ok(PathBuf::from(server_path).join(relative))
+}
+
+/// Checks an id sent by the frontend before it becomes part of a path. [Ex: a server's
+/// UUID, or a backup id like "2024-05-01_18-30-00"]
+///
+/// Only ASCII letters, digits, `-` and `_` are accepted, so an id can never be `..`
+/// or contain a separator.
+pub fn validate_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !valid {
+        return Err(format!("Invalid id: {}", id));
+    }
+
+    Ok(())
+}
+
+/// Checks a single folder name (server names, versions read from a backup...) before it is
+/// joined into a path, refusing separators and names made only of dots.
+pub fn validate_dir_name(name: &str) -> Result<(), String> {
+    let trimmed = name.trim();
+
+    if trimmed.is_empty() || trimmed.chars().all(|c| c == '.') {
+        return Err(format!("Invalid name: {}", name));
+    }
+
+    if trimmed.contains(['/', '\\', ':', '\0']) {
+        return Err(format!("{} can't contain / \\ or :", name));
+    }
+
+    Ok(())
+}
+
+/// Returns the `saves` folder of the official Minecraft launcher, where singleplayer worlds live.
+///
+/// - Windows: C:\Users\<you>\AppData\Roaming\.minecraft\saves
+/// - macOS:   ~/Library/Application Support/minecraft/saves
+/// - Linux:   ~/.minecraft/saves
+///
+/// This function does NOT check that the folder exists.
+pub fn minecraft_saves_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let base = dirs::data_dir()?.join(".minecraft");
+
+    #[cfg(target_os = "macos")]
+    let base = dirs::data_dir()?.join("minecraft");
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let base = dirs::home_dir()?.join(".minecraft");
+
+    Some(base.join("saves"))
+}
@@ -0,0 +1,513 @@
+use std::{collections::HashMap, fs, path::Path};
+
+/// A .properties file (Java `Properties` format) that can be edited without losing anything.
+///
+/// Comments, blank lines, key order and the exact text of untouched entries (escapes,
+/// continuation lines, separators) are written back as they were read. Only entries that
+/// are set are re-serialized.
+#[derive(Debug, Clone)]
+pub struct PropertiesDocument {
+    lines: Vec<Line>,
+    newline: &'static str,
+    trailing_newline: bool,
+    encoding: Encoding,
+}
+
+// How the file was stored, `save` writes it back the same way
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Utf8 { bom: bool },
+    Latin1,
+}
+
+#[derive(Debug, Clone)]
+enum Line {
+    // Comment or blank line, kept verbatim
+    Other(String),
+    Entry {
+        key: String,
+        value: String,
+        // Original text (may span several physical lines), None once the entry was changed
+        raw: Option<String>,
+    },
+}
+
+impl PropertiesDocument {
+    pub fn parse(content: &str) -> Self {
+        let newline = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let trailing_newline = content.is_empty() || content.ends_with('\n');
+
+        let physical: Vec<&str> = content
+            .strip_suffix('\n')
+            .unwrap_or(content)
+            .split('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .collect();
+
+        let mut lines = Vec::new();
+        let mut i = 0;
+
+        while i < physical.len() && !content.is_empty() {
+            let first = physical[i];
+            let trimmed = first.trim_start_matches([' ', '\t', '\x0c']);
+
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('!') {
+                lines.push(Line::Other(first.to_string()));
+                i += 1;
+                continue;
+            }
+
+            // A line ending in an odd number of backslashes continues on the next one
+            let mut logical = String::new();
+            let mut raw = Vec::new();
+            let mut part = trimmed;
+
+            loop {
+                raw.push(physical[i]);
+                i += 1;
+
+                let slashes = part.chars().rev().take_while(|c| *c == '\\').count();
+                if slashes % 2 == 1 && i < physical.len() {
+                    logical.push_str(&part[..part.len() - 1]);
+                    part = physical[i].trim_start_matches([' ', '\t', '\x0c']);
+                } else {
+                    logical.push_str(part);
+                    break;
+                }
+            }
+
+            let (key, value) = split_entry(&logical);
+            lines.push(Line::Entry {
+                key,
+                value,
+                raw: Some(raw.join(newline)),
+            });
+        }
+
+        Self {
+            lines,
+            newline,
+            trailing_newline,
+            encoding: Encoding::Utf8 { bom: false },
+        }
+    }
+
+    /// Reads a file as UTF-8, falling back to ISO-8859-1 like the server does.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+
+        let (content, encoding) = match String::from_utf8(bytes) {
+            Ok(content) => match content.strip_prefix('\u{feff}') {
+                Some(rest) => (rest.to_string(), Encoding::Utf8 { bom: true }),
+                None => (content, Encoding::Utf8 { bom: false }),
+            },
+            Err(e) => (
+                e.into_bytes().iter().map(|b| *b as char).collect(),
+                Encoding::Latin1,
+            ),
+        };
+
+        let mut doc = Self::parse(&content);
+        doc.encoding = encoding;
+        Ok(doc)
+    }
+
+    /// Writes the file in the encoding it was loaded with. Changed entries are pure ASCII,
+    /// so an ISO-8859-1 file stays readable by older servers.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = self.to_string();
+
+        let bytes = match self.encoding {
+            Encoding::Utf8 { bom } => {
+                let mut bytes = if bom { "\u{feff}".into() } else { Vec::new() };
+                bytes.extend(text.into_bytes());
+                bytes
+            }
+            Encoding::Latin1 => text
+                .chars()
+                .map(|c| u8::try_from(c).map_err(|_| format!("{} isn't ISO-8859-1", c)))
+                .collect::<Result<_, _>>()?,
+        };
+
+        fs::write(path, bytes).map_err(|e| e.to_string())
+    }
+
+    // Like Java, the last occurrence of a duplicated key wins
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().rev().find_map(|line| match line {
+            Line::Entry { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Changes a value in place, or appends the key at the end when it's new.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let mut found = false;
+
+        for line in self.lines.iter_mut() {
+            if let Line::Entry {
+                key: k,
+                value: v,
+                raw,
+            } = line
+            {
+                if k == key {
+                    found = true;
+                    if v != value {
+                        *v = value.to_string();
+                        *raw = None;
+                    }
+                }
+            }
+        }
+
+        if !found {
+            self.lines.push(Line::Entry {
+                key: key.to_string(),
+                value: value.to_string(),
+                raw: None,
+            });
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> bool {
+        let before = self.lines.len();
+        self.lines
+            .retain(|line| !matches!(line, Line::Entry { key: k, .. } if k == key));
+        before != self.lines.len()
+    }
+
+    /// Every key and value in file order, duplicates only once (at their last position).
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries: Vec<(String, String)> = Vec::new();
+
+        for line in &self.lines {
+            if let Line::Entry { key, value, .. } = line {
+                entries.retain(|(k, _)| k != key);
+                entries.push((key.clone(), value.clone()));
+            }
+        }
+
+        entries
+    }
+
+    pub fn to_map(&self) -> HashMap<String, String> {
+        self.entries().into_iter().collect()
+    }
+}
+
+impl std::fmt::Display for PropertiesDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text: Vec<String> = self
+            .lines
+            .iter()
+            .map(|line| match line {
+                Line::Other(text) => text.clone(),
+                Line::Entry { raw: Some(raw), .. } => raw.clone(),
+                Line::Entry { key, value, .. } => {
+                    format!("{}={}", escape(key, true), escape(value, false))
+                }
+            })
+            .collect();
+
+        write!(f, "{}", text.join(self.newline))?;
+
+        if self.trailing_newline && !text.is_empty() {
+            write!(f, "{}", self.newline)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Splits a logical line into its unescaped key and value
+fn split_entry(line: &str) -> (String, String) {
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+
+    // The key ends at the first unescaped '=', ':' or whitespace
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '=' | ':' | ' ' | '\t' | '\x0c' => break,
+            _ => i += 1,
+        }
+    }
+    i = i.min(chars.len());
+    let key_end = i;
+
+    // Whitespace, at most one separator, then whitespace again
+    while i < chars.len() && matches!(chars[i], ' ' | '\t' | '\x0c') {
+        i += 1;
+    }
+    if i < chars.len() && matches!(chars[i], '=' | ':') {
+        i += 1;
+    }
+    while i < chars.len() && matches!(chars[i], ' ' | '\t' | '\x0c') {
+        i += 1;
+    }
+
+    let key: String = chars[..key_end].iter().collect();
+    let value: String = chars[i..].iter().collect();
+
+    (unescape(&key), unescape(&value))
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\x0c'),
+            Some('u') => {
+                let hex: String = (0..4).filter_map(|_| chars.next()).collect();
+                let code = u32::from_str_radix(&hex, 16).unwrap_or(0xfffd);
+
+                // Characters outside the BMP are written as a \uXXXX\uXXXX surrogate pair
+                if (0xd800..0xdc00).contains(&code) {
+                    let mut lookahead = chars.clone();
+                    if lookahead.next() == Some('\\') && lookahead.next() == Some('u') {
+                        let low: String = (0..4).filter_map(|_| lookahead.next()).collect();
+                        if let Ok(low) = u32::from_str_radix(&low, 16) {
+                            if (0xdc00..0xe000).contains(&low) {
+                                chars = lookahead;
+                                let combined = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                                out.push(char::from_u32(combined).unwrap_or('\u{fffd}'));
+                                continue;
+                            }
+                        }
+                    }
+                }
+
+                out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+
+    out
+}
+
+// Non-ASCII is written as \uXXXX so the file reads the same as UTF-8 or ISO-8859-1
+fn escape(text: &str, is_key: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\x0c' => out.push_str("\\f"),
+            '=' | ':' if is_key => {
+                out.push('\\');
+                out.push(c);
+            }
+            '#' | '!' if is_key && i == 0 => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' ' if is_key || i == 0 => out.push_str("\\ "),
+            c if (c as u32) < 0x20 || (c as u32) > 0x7e => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{:04X}", unit));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // As written by a fresh 1.21.1 server
+    const VANILLA: &str = "#Minecraft server properties
+#Sat Oct 17 14:02:51 CEST 2026
+accepts-transfers=false
+allow-flight=false
+allow-nether=true
+broadcast-console-to-ops=true
+broadcast-rcon-to-ops=true
+bug-report-link=
+difficulty=easy
+enable-command-block=false
+enable-jmx-monitoring=false
+enable-query=false
+enable-rcon=false
+enable-status=true
+enforce-secure-profile=true
+enforce-whitelist=false
+entity-broadcast-range-percentage=100
+force-gamemode=false
+function-permission-level=2
+gamemode=survival
+generate-structures=true
+generator-settings={}
+hardcore=false
+hide-online-players=false
+initial-disabled-packs=
+initial-enabled-packs=vanilla
+level-name=world
+level-seed=
+level-type=minecraft\\:normal
+log-ips=true
+max-chained-neighbor-updates=1000000
+max-players=20
+max-tick-time=60000
+max-world-size=29999984
+motd=A Minecraft Server
+network-compression-threshold=256
+online-mode=true
+op-permission-level=4
+player-idle-timeout=0
+prevent-proxy-connections=false
+pvp=true
+query.port=25565
+rate-limit=0
+rcon.password=
+rcon.port=25575
+region-file-compression=deflate
+require-resource-pack=false
+resource-pack=
+resource-pack-id=
+resource-pack-prompt=
+resource-pack-sha1=
+server-ip=
+server-port=25565
+simulation-distance=10
+spawn-animals=true
+spawn-monsters=true
+spawn-npcs=true
+spawn-protection=16
+sync-chunk-writes=true
+text-filtering-config=
+use-native-transport=true
+view-distance=10
+white-list=false
+";
+
+    fn assert_round_trip(content: &str) -> PropertiesDocument {
+        let doc = PropertiesDocument::parse(content);
+        assert_eq!(doc.to_string(), content);
+        doc
+    }
+
+    #[test]
+    fn vanilla_file_round_trips() {
+        let doc = assert_round_trip(VANILLA);
+
+        assert_eq!(doc.get("level-type"), Some("minecraft:normal"));
+        assert_eq!(doc.get("generator-settings"), Some("{}"));
+        assert_eq!(doc.get("level-seed"), Some(""));
+        assert_eq!(doc.entries().len(), 61);
+    }
+
+    #[test]
+    fn crlf_file_round_trips() {
+        let doc = assert_round_trip(&VANILLA.replace('\n', "\r\n"));
+        assert_eq!(doc.get("motd"), Some("A Minecraft Server"));
+
+        // Without a final line break
+        assert_round_trip("#comment\r\na=1\r\nb=2");
+    }
+
+    #[test]
+    fn continuation_lines_round_trip() {
+        let content = "  long = first \\\n     second\\\n\tthird\nnext=x\\\\\nlast=y\\";
+        let doc = assert_round_trip(content);
+
+        assert_eq!(doc.get("long"), Some("first secondthird"));
+        // An even number of backslashes is an escaped backslash, not a continuation
+        assert_eq!(doc.get("next"), Some("x\\"));
+        // A continuation on the last line continues into nothing
+        assert_eq!(doc.get("last"), Some("y"));
+    }
+
+    #[test]
+    fn unicode_escapes_round_trip() {
+        let content =
+            "motd=\\u00a7aCaf\\u00E9 \\uD83D\\uDE00\nlone=\\uD83D!\nkey\\ with\\=eq : v\n";
+        let doc = assert_round_trip(content);
+
+        assert_eq!(doc.get("motd"), Some("§aCafé 😀"));
+        // An unpaired surrogate can't be a char
+        assert_eq!(doc.get("lone"), Some("\u{fffd}!"));
+        assert_eq!(doc.get("key with=eq"), Some("v"));
+    }
+
+    #[test]
+    fn save_keeps_the_encoding() {
+        let dir = std::env::temp_dir().join(format!("cubely-properties-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("server.properties");
+
+        // Not valid UTF-8, so read as ISO-8859-1 with \xE9 as é
+        fs::write(&path, b"#Caf\xE9\nmotd=Caf\xE9\npvp=true\n").unwrap();
+        let mut doc = PropertiesDocument::load(&path).unwrap();
+        assert_eq!(doc.get("motd"), Some("Caf\u{e9}"));
+
+        doc.set("pvp", "false");
+        doc.set("level-name", "\u{e9}t\u{e9}");
+        doc.save(&path).unwrap();
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            b"#Caf\xE9\nmotd=Caf\xE9\npvp=false\nlevel-name=\\u00E9t\\u00E9\n"
+        );
+
+        // UTF-8 stays UTF-8, byte order mark included
+        fs::write(&path, "\u{feff}motd=Caf\u{e9}\n").unwrap();
+        let mut doc = PropertiesDocument::load(&path).unwrap();
+        assert_eq!(doc.get("motd"), Some("Caf\u{e9}"));
+
+        doc.set("pvp", "true");
+        doc.save(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "\u{feff}motd=Caf\u{e9}\npvp=true\n"
+        );
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn set_only_changes_the_target_line() {
+        let content = VANILLA.replace('\n', "\r\n");
+        let mut doc = PropertiesDocument::parse(&content);
+
+        doc.set("motd", "Caf\u{e9} 😀");
+        doc.set("pvp", "true"); // unchanged value keeps its original text
+
+        let expected = content.replace("motd=A Minecraft Server", "motd=Caf\\u00E9 \\uD83D\\uDE00");
+        assert_eq!(doc.to_string(), expected);
+        assert_eq!(
+            PropertiesDocument::parse(&expected).get("motd"),
+            Some("Caf\u{e9} 😀")
+        );
+
+        // New keys are appended at the end
+        doc.set("new key", " lead");
+        assert_eq!(
+            doc.to_string(),
+            format!("{}new\\ key=\\ lead\r\n", expected)
+        );
+    }
+}
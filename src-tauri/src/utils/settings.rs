@@ -0,0 +1,38 @@
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::remote_backups::BackupDestination;
+use crate::utils::path::app_settings_path;
+
+/// App-wide settings stored in `app_settings_path()`.
+///
+/// Everything here is optional so that older settings files keep loading
+/// when new fields are added.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AppSettings {
+    #[serde(default)]
+    pub backup_destinations: Vec<BackupDestination>,
+}
+
+/// Reads the settings file, returning the defaults when it does not exist yet.
+pub fn load_settings() -> Result<AppSettings, String> {
+    let path = app_settings_path();
+
+    if !path.exists() {
+        return Ok(AppSettings::default());
+    }
+
+    let raw = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&raw).map_err(|e| format!("Invalid settings file: {}", e))
+}
+
+pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
+    let path = app_settings_path();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    fs::write(&path, serde_json::to_string_pretty(settings).unwrap()).map_err(|e| e.to_string())
+}